@@ -1,15 +1,253 @@
 #![no_std]
 
-use soroban_sdk::{Address, Bytes, BytesN, Env, IntoVal, String, Symbol, contract, contractimpl, contracttype, vec};
+use soroban_sdk::{token, xdr::ToXdr, Address, Bytes, BytesN, Env, IntoVal, InvokeError, Map, String, Symbol, TryFromVal, Val, contract, contracterror, contractevent, contractimpl, contracttype, vec};
+
+const DAY_IN_LEDGERS: u32 = 17280;
+// Per-token and per-user records live in persistent storage, so the instance
+// entry (loaded on every call) does not grow with each mint
+const RECORD_TTL_THRESHOLD: u32 = 30 * DAY_IN_LEDGERS;
+const RECORD_TTL_EXTEND_TO: u32 = 120 * DAY_IN_LEDGERS;
+// Proof section sizes, in 32-byte fields, the UltraHonk verifier accepts. It
+// ignores the blob's count header and treats whatever lies between the header
+// and the proof section as public inputs.
+const ULTRAHONK_PROOF_FIELDS: [u32; 2] = [456, 440];
 
 // Create a DataKey type for storing admin and contract addresses
 #[contracttype]
 enum DataKey {
     Admin,
+    PendingAdmin,
     ZKVerifierContract,
     InzpektorIDNFTContract,
+    CredentialCollections, // credential_type -> NFT contract
+    PostMintHook,
+    PostMintHookStrict,
+    TokenProof(Address, u32), // (NFT contract, token_id) -> verifier proof id
+    TokenVerifier(Address, u32), // (NFT contract, token_id) -> verifier that validated it
+    TotalMinted,
+    VerifierAbi,
+    MintCooldown,
+    LastMint(Address), // user -> timestamp of their last mint
+    SelfMint,
+    ConsumedCallback,
+    MintWindowLimit,
+    MintWindow, // (window start, mints in window)
+    AllowedTier(Symbol),
+    TokenTier(Address, u32), // (NFT contract, token_id) -> tier
+    SubjectInputIndex,
+    ProofSystem(Symbol), // proof system -> verifier routing
+    UsedProof(BytesN<32>), // keccak256(proof_blob) of every proof that minted
 }
 
+/// How `vk_json` and `proof_blob` are passed to the verifier function
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VerifierArgs {
+    /// `fn(vk_json, proof_blob)`, as in the UltraHonk `verify_proof`
+    VkThenProof,
+    /// `fn(proof_blob, vk_json)`
+    ProofThenVk,
+    /// `fn(proof_blob)`, for verifiers that keep their key on-chain
+    ProofOnly,
+}
+
+/// Function the handler calls on the verifier contract, and its argument layout
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VerifierAbi {
+    pub function: Symbol,
+    pub args: VerifierArgs,
+}
+
+/// Whether users may mint for themselves, for how long those IDs last and
+/// which verification key their proofs must use
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SelfMintConfig {
+    pub enabled: bool,
+    /// Seconds from mint until expiration; 0 mints IDs that never expire
+    pub validity_secs: u64,
+    /// keccak256 of the only `vk_json` accepted by `self_mint`
+    pub vk_hash: BytesN<32>,
+}
+
+/// Global cap on mints per fixed time window
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MintWindowLimit {
+    /// 0 disables the limit
+    pub max_mints: u32,
+    pub window_secs: u64,
+}
+
+/// Verifier contract and ABI handling one proof system
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProofSystemRoute {
+    pub verifier_contract: Address,
+    pub abi: VerifierAbi,
+}
+
+#[contracterror]
+#[repr(u32)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Error {
+    MintVerificationFailed = 1,
+    UnknownCredentialType = 2,
+    Unauthorized = 3,
+    PostMintHookFailed = 4,
+    NoPendingAdmin = 5,
+    InvalidExpiration = 6,
+    CooldownActive = 7,
+    SelfMintDisabled = 8,
+    TokenIdMismatch = 9,
+    WindowLimitReached = 10,
+    EmptyInput = 11,
+    TierNotAllowed = 12,
+    SubjectMismatch = 13,
+    ProofInvalid = 14,
+    UnknownProofSystem = 15,
+    VkMismatch = 16,
+    ProofAlreadyUsed = 17,
+    SubjectBindingRequired = 18,
+    InvalidMintWindow = 19,
+}
+
+// Admin audit events, published under ("admin", <action>) topics so governance
+// history can be reconstructed from events alone
+
+#[contractevent(topics = ["admin", "initialize"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Initialized {
+    pub admin: Address,
+    pub verifier_contract: Address,
+    pub nft_contract: Address,
+}
+
+#[contractevent(topics = ["admin", "propose"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminProposed {
+    pub admin: Address,
+    pub new_admin: Address,
+}
+
+#[contractevent(topics = ["admin", "accept"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminAccepted {
+    pub previous_admin: Address,
+    pub admin: Address,
+}
+
+#[contractevent(topics = ["admin", "set_verifier"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VerifierContractSet {
+    pub admin: Address,
+    pub verifier_contract: Address,
+}
+
+#[contractevent(topics = ["admin", "set_verifier_abi"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VerifierAbiSet {
+    pub admin: Address,
+    pub abi: VerifierAbi,
+}
+
+#[contractevent(topics = ["admin", "set_nft"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NftContractSet {
+    pub admin: Address,
+    pub nft_contract: Address,
+}
+
+#[contractevent(topics = ["admin", "set_collection"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CredentialCollectionSet {
+    pub admin: Address,
+    pub credential_type: Symbol,
+    pub nft_contract: Address,
+}
+
+#[contractevent(topics = ["admin", "remove_collection"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CredentialCollectionRemoved {
+    pub admin: Address,
+    pub credential_type: Symbol,
+}
+
+#[contractevent(topics = ["admin", "set_hook"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PostMintHookSet {
+    pub admin: Address,
+    pub hook: Option<Address>,
+    pub strict: bool,
+}
+
+#[contractevent(topics = ["admin", "set_cooldown"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MintCooldownSet {
+    pub admin: Address,
+    pub cooldown_secs: u64,
+}
+
+#[contractevent(topics = ["admin", "set_self_mint"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SelfMintSet {
+    pub admin: Address,
+    pub config: SelfMintConfig,
+}
+
+#[contractevent(topics = ["admin", "set_consumed_callback"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ConsumedCallbackSet {
+    pub admin: Address,
+    pub function: Option<Symbol>,
+}
+
+#[contractevent(topics = ["admin", "set_window_limit"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MintWindowLimitSet {
+    pub admin: Address,
+    pub limit: MintWindowLimit,
+}
+
+#[contractevent(topics = ["admin", "set_tier"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TierAllowedSet {
+    pub admin: Address,
+    pub tier: Symbol,
+    pub allowed: bool,
+}
+
+#[contractevent(topics = ["admin", "set_subject_binding"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubjectBindingSet {
+    pub admin: Address,
+    pub input_index: Option<u32>,
+}
+
+#[contractevent(topics = ["admin", "set_proof_system"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProofSystemSet {
+    pub admin: Address,
+    pub proof_system: Symbol,
+    pub route: ProofSystemRoute,
+}
+
+#[contractevent(topics = ["admin", "remove_proof_system"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProofSystemRemoved {
+    pub admin: Address,
+    pub proof_system: Symbol,
+}
+
+#[contractevent(topics = ["admin", "sweep"])]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Swept {
+    pub admin: Address,
+    pub token: Address,
+    pub to: Address,
+    pub amount: i128,
+}
 
 #[contract]
 pub struct InzpektorHandlerContract;
@@ -26,35 +264,606 @@ impl InzpektorHandlerContract {
         e.storage().instance().set(&DataKey::Admin, &admin);
         e.storage().instance().set(&DataKey::ZKVerifierContract, &verifier_contract);
         e.storage().instance().set(&DataKey::InzpektorIDNFTContract, &inzpektor_id_contract);
+
+        Initialized {
+            admin,
+            verifier_contract,
+            nft_contract: inzpektor_id_contract,
+        }
+        .publish(&e);
     }
 
-    pub fn mint_inzpektor_id(e: Env, user: Address, expires_at: u64, vk_json: Bytes, proof_blob: Bytes) -> u32 {
-      let actual_admin: Address = e.storage().instance().get(&DataKey::Admin).expect("admin not set");
-      actual_admin.require_auth();
+    /// First step of an admin handoff; `new_admin` must call `accept_admin`
+    pub fn propose_admin(e: Env, current_admin: Address, new_admin: Address) -> Result<(), Error> {
+        if current_admin != Self::get_admin(e.clone()) {
+            return Err(Error::Unauthorized);
+        }
+        current_admin.require_auth();
 
-      // Verify proof by calling the verifier contract
-      let verifier_contract_address: Address = e.storage().instance().get(&DataKey::ZKVerifierContract).expect("verifier not set");
+        e.storage().instance().set(&DataKey::PendingAdmin, &new_admin);
 
-      // Call verify_proof on the ultrahonk verifier contract
-      let verify_fn = Symbol::new(&e, "verify_proof");
-      let _proof_id: BytesN<32> = e.invoke_contract(
-          &verifier_contract_address,
-          &verify_fn,
-          vec![&e, vk_json.into_val(&e), proof_blob.into_val(&e)]
-      );
+        AdminProposed { admin: current_admin, new_admin }.publish(&e);
+        Ok(())
+    }
+
+    /// Complete an admin handoff started with `propose_admin`
+    pub fn accept_admin(e: Env, new_admin: Address) -> Result<(), Error> {
+        let pending_admin = Self::get_pending_admin(e.clone()).ok_or(Error::NoPendingAdmin)?;
+        if new_admin != pending_admin {
+            return Err(Error::Unauthorized);
+        }
+        new_admin.require_auth();
+
+        let previous_admin = Self::get_admin(e.clone());
+        e.storage().instance().set(&DataKey::Admin, &new_admin);
+        e.storage().instance().remove(&DataKey::PendingAdmin);
+
+        AdminAccepted { previous_admin, admin: new_admin }.publish(&e);
+        Ok(())
+    }
+
+    pub fn get_pending_admin(e: Env) -> Option<Address> {
+        e.storage().instance().get(&DataKey::PendingAdmin)
+    }
+
+    pub fn set_verifier_contract(e: Env, verifier_contract: Address) {
+        let admin = Self::require_admin(&e);
+        e.storage().instance().set(&DataKey::ZKVerifierContract, &verifier_contract);
+
+        VerifierContractSet { admin, verifier_contract }.publish(&e);
+    }
+
+    /// Point the handler at a verifier with a different ABI (e.g. Groth16)
+    pub fn set_verifier_abi(e: Env, function: Symbol, args: VerifierArgs) {
+        let admin = Self::require_admin(&e);
+
+        let abi = VerifierAbi { function, args };
+        e.storage().instance().set(&DataKey::VerifierAbi, &abi);
+
+        VerifierAbiSet { admin, abi }.publish(&e);
+    }
+
+    /// Defaults to the UltraHonk verifier's `verify_proof(vk_json, proof_blob)`
+    pub fn get_verifier_abi(e: Env) -> VerifierAbi {
+        e.storage()
+            .instance()
+            .get(&DataKey::VerifierAbi)
+            .unwrap_or(VerifierAbi {
+                function: Symbol::new(&e, "verify_proof"),
+                args: VerifierArgs::VkThenProof,
+            })
+    }
+
+    pub fn set_nft_contract(e: Env, nft_contract: Address) {
+        let admin = Self::require_admin(&e);
+        e.storage().instance().set(&DataKey::InzpektorIDNFTContract, &nft_contract);
+
+        NftContractSet { admin, nft_contract }.publish(&e);
+    }
+
+    pub fn mint_inzpektor_id(e: Env, user: Address, expires_at: u64, vk_json: Bytes, proof_blob: Bytes) -> Result<u32, Error> {
+      Self::require_admin(&e);
 
-      // Proof verified successfully, mint INZPEKTOR-ID NFT with expiration
       let inzpektor_id_contract_address: Address = e.storage().instance().get(&DataKey::InzpektorIDNFTContract).expect("INZPEKTOR-ID contract not set");
 
-      // Call mint on the NFT contract with recipient and expiration
-      let mint_fn = Symbol::new(&e, "mint");
-      let token_id: u32 = e.invoke_contract(
-          &inzpektor_id_contract_address,
-          &mint_fn,
-          vec![&e, user.into_val(&e), expires_at.into_val(&e)]
-      );
+      Self::verify_and_mint(&e, &inzpektor_id_contract_address, user, expires_at, vk_json, proof_blob)
+    }
+
+    /// Mint with the user's consent proven by a Soroban authorization entry
+    /// over `(expires_at, keccak256(proof_blob))`, so the admin can submit the
+    /// transaction while the user is offline
+    pub fn mint_with_consent(e: Env, user: Address, expires_at: u64, vk_json: Bytes, proof_blob: Bytes) -> Result<u32, Error> {
+        Self::require_admin(&e);
+
+        let proof_hash: BytesN<32> = e.crypto().keccak256(&proof_blob).into();
+        user.require_auth_for_args(vec![&e, expires_at.into_val(&e), proof_hash.into_val(&e)]);
+
+        let inzpektor_id_contract_address = Self::get_nft_contract(e.clone());
+        Self::verify_and_mint(&e, &inzpektor_id_contract_address, user, expires_at, vk_json, proof_blob)
+    }
+
+    /// Permissionless mint: the user submits their own proof and receives the
+    /// ID without going through the admin. Only available while enabled with
+    /// `set_self_mint`; the expiration comes from the configured validity.
+    /// `vk_json` must be the key pinned by the admin, and the proof must be
+    /// bound to `user` with `set_subject_binding`.
+    pub fn self_mint(e: Env, user: Address, vk_json: Bytes, proof_blob: Bytes) -> Result<u32, Error> {
+        let config = Self::get_self_mint(e.clone())
+            .filter(|config| config.enabled)
+            .ok_or(Error::SelfMintDisabled)?;
+        user.require_auth();
+
+        // Proofs are public once submitted; without the binding anyone could
+        // mint with someone else's
+        if Self::get_subject_binding(e.clone()).is_none() {
+            return Err(Error::SubjectBindingRequired);
+        }
+
+        // A caller-chosen key would let anyone prove a trivial circuit
+        let vk_hash: BytesN<32> = e.crypto().keccak256(&vk_json).into();
+        if vk_hash != config.vk_hash {
+            return Err(Error::VkMismatch);
+        }
+
+        let expires_at = match config.validity_secs {
+            0 => 0,
+            validity_secs => e.ledger().timestamp().saturating_add(validity_secs),
+        };
+
+        let inzpektor_id_contract_address = Self::get_nft_contract(e.clone());
+        Self::verify_and_mint(&e, &inzpektor_id_contract_address, user, expires_at, vk_json, proof_blob)
+    }
+
+    /// Configure self-minting; `vk_hash` is keccak256 of the verification
+    /// key self-minted proofs must be checked against. Enabling requires
+    /// `set_subject_binding` to be configured first.
+    pub fn set_self_mint(e: Env, enabled: bool, validity_secs: u64, vk_hash: BytesN<32>) -> Result<(), Error> {
+        let admin = Self::require_admin(&e);
+
+        if enabled && Self::get_subject_binding(e.clone()).is_none() {
+            return Err(Error::SubjectBindingRequired);
+        }
+
+        let config = SelfMintConfig { enabled, validity_secs, vk_hash };
+        e.storage().instance().set(&DataKey::SelfMint, &config);
+
+        SelfMintSet { admin, config }.publish(&e);
+        Ok(())
+    }
+
+    /// Self-minting is disabled until configured
+    pub fn get_self_mint(e: Env) -> Option<SelfMintConfig> {
+        e.storage().instance().get(&DataKey::SelfMint)
+    }
+
+    /// Mint from the NFT collection registered for `credential_type`
+    pub fn mint_credential(e: Env, user: Address, expires_at: u64, vk_json: Bytes, proof_blob: Bytes, credential_type: Symbol) -> Result<u32, Error> {
+        Self::require_admin(&e);
+
+        let nft_contract_address = Self::get_credential_collection(e.clone(), credential_type)
+            .ok_or(Error::UnknownCredentialType)?;
+
+        Self::verify_and_mint(&e, &nft_contract_address, user, expires_at, vk_json, proof_blob)
+    }
+
+    /// Mint with a tier the caller derived from the proof's public inputs
+    /// (e.g. an age bracket). The tier must be in the allowed set and is
+    /// recorded against the token.
+    pub fn mint_with_tier(e: Env, user: Address, expires_at: u64, vk_json: Bytes, proof_blob: Bytes, tier: Symbol) -> Result<u32, Error> {
+        Self::require_admin(&e);
+
+        if !Self::is_tier_allowed(e.clone(), tier.clone()) {
+            return Err(Error::TierNotAllowed);
+        }
+
+        let inzpektor_id_contract_address = Self::get_nft_contract(e.clone());
+        let token_id = Self::verify_and_mint(&e, &inzpektor_id_contract_address, user, expires_at, vk_json, proof_blob)?;
+        Self::set_record(&e, &DataKey::TokenTier(inzpektor_id_contract_address, token_id), &tier);
+
+        Ok(token_id)
+    }
+
+    pub fn set_tier_allowed(e: Env, tier: Symbol, allowed: bool) {
+        let admin = Self::require_admin(&e);
+
+        if allowed {
+            e.storage().instance().set(&DataKey::AllowedTier(tier.clone()), &true);
+        } else {
+            e.storage().instance().remove(&DataKey::AllowedTier(tier.clone()));
+        }
+
+        TierAllowedSet { admin, tier, allowed }.publish(&e);
+    }
+
+    pub fn is_tier_allowed(e: Env, tier: Symbol) -> bool {
+        e.storage().instance().has(&DataKey::AllowedTier(tier))
+    }
+
+    /// Tier recorded for `token_id` on the INZPEKTOR-ID collection
+    pub fn get_token_tier(e: Env, token_id: u32) -> Option<Symbol> {
+        let inzpektor_id_contract_address = Self::get_nft_contract(e.clone());
+        Self::get_record(&e, &DataKey::TokenTier(inzpektor_id_contract_address, token_id))
+    }
+
+    /// Mint with a proof from `proof_system`, verified by the verifier and
+    /// ABI routed for that system with `set_proof_system`
+    pub fn mint_with_proof_system(e: Env, user: Address, expires_at: u64, vk_json: Bytes, proof_blob: Bytes, proof_system: Symbol) -> Result<u32, Error> {
+        Self::require_admin(&e);
+
+        let route = Self::get_proof_system(e.clone(), proof_system).ok_or(Error::UnknownProofSystem)?;
+
+        let inzpektor_id_contract_address = Self::get_nft_contract(e.clone());
+        Self::verify_and_mint_with(&e, &inzpektor_id_contract_address, &route.verifier_contract, &route.abi, user, expires_at, vk_json, proof_blob)
+    }
+
+    /// Route `proof_system` (e.g. `ultrahonk`, `groth16`) to a verifier
+    /// contract and the function it exposes
+    pub fn set_proof_system(e: Env, proof_system: Symbol, verifier_contract: Address, function: Symbol, args: VerifierArgs) {
+        let admin = Self::require_admin(&e);
+
+        let route = ProofSystemRoute {
+            verifier_contract,
+            abi: VerifierAbi { function, args },
+        };
+        e.storage().instance().set(&DataKey::ProofSystem(proof_system.clone()), &route);
+
+        ProofSystemSet { admin, proof_system, route }.publish(&e);
+    }
+
+    pub fn remove_proof_system(e: Env, proof_system: Symbol) {
+        let admin = Self::require_admin(&e);
+        e.storage().instance().remove(&DataKey::ProofSystem(proof_system.clone()));
+
+        ProofSystemRemoved { admin, proof_system }.publish(&e);
+    }
+
+    pub fn get_proof_system(e: Env, proof_system: Symbol) -> Option<ProofSystemRoute> {
+        e.storage().instance().get(&DataKey::ProofSystem(proof_system))
+    }
+
+    /// Register (or replace) the NFT contract issuing `credential_type`
+    pub fn set_credential_collection(e: Env, credential_type: Symbol, nft_contract: Address) {
+        let admin = Self::require_admin(&e);
+
+        let mut collections = Self::credential_collections(&e);
+        collections.set(credential_type.clone(), nft_contract.clone());
+        e.storage().instance().set(&DataKey::CredentialCollections, &collections);
+
+        CredentialCollectionSet { admin, credential_type, nft_contract }.publish(&e);
+    }
+
+    pub fn remove_credential_collection(e: Env, credential_type: Symbol) {
+        let admin = Self::require_admin(&e);
+
+        let mut collections = Self::credential_collections(&e);
+        collections.remove(credential_type.clone());
+        e.storage().instance().set(&DataKey::CredentialCollections, &collections);
+
+        CredentialCollectionRemoved { admin, credential_type }.publish(&e);
+    }
+
+    pub fn get_credential_collection(e: Env, credential_type: Symbol) -> Option<Address> {
+        Self::credential_collections(&e).get(credential_type)
+    }
+
+    fn require_admin(e: &Env) -> Address {
+        let admin: Address = e.storage().instance().get(&DataKey::Admin).expect("admin not set");
+        admin.require_auth();
+        admin
+    }
+
+    fn set_record<V: IntoVal<Env, Val>>(e: &Env, key: &DataKey, value: &V) {
+        e.storage().persistent().set(key, value);
+        e.storage().persistent().extend_ttl(key, RECORD_TTL_THRESHOLD, RECORD_TTL_EXTEND_TO);
+    }
+
+    /// Read a persistent record, keeping it alive while it is still in use
+    fn get_record<V: TryFromVal<Env, Val>>(e: &Env, key: &DataKey) -> Option<V> {
+        let value = e.storage().persistent().get(key);
+        if value.is_some() {
+            e.storage().persistent().extend_ttl(key, RECORD_TTL_THRESHOLD, RECORD_TTL_EXTEND_TO);
+        }
+        value
+    }
+
+    fn credential_collections(e: &Env) -> Map<Symbol, Address> {
+        e.storage()
+            .instance()
+            .get(&DataKey::CredentialCollections)
+            .unwrap_or(Map::new(e))
+    }
+
+    /// Start and mint count of the current window, starting a fresh one once
+    /// `window_secs` have passed since the last began
+    fn current_mint_window(e: &Env, limit: &MintWindowLimit) -> (u64, u32) {
+        let now = e.ledger().timestamp();
+        match e.storage().instance().get::<_, (u64, u32)>(&DataKey::MintWindow) {
+            Some((start, mints)) if now < start.saturating_add(limit.window_secs) => (start, mints),
+            _ => (now, 0),
+        }
+    }
+
+    /// Interpret the verifier's return value instead of assuming that any
+    /// non-trapping call means success. A proof id is accepted unless it is
+    /// all zeroes; a bool must be true, in which case the id is
+    /// keccak256(proof_blob) as the UltraHonk verifier computes it.
+    fn check_verify_result(e: &Env, verify_result: Val, proof_blob: &Bytes) -> Result<BytesN<32>, Error> {
+        if let Ok(proof_id) = BytesN::<32>::try_from_val(e, &verify_result) {
+            if proof_id == BytesN::from_array(e, &[0u8; 32]) {
+                return Err(Error::ProofInvalid);
+            }
+            return Ok(proof_id);
+        }
+        match bool::try_from_val(e, &verify_result) {
+            Ok(true) => Ok(e.crypto().keccak256(proof_blob).into()),
+            _ => Err(Error::ProofInvalid),
+        }
+    }
 
-      token_id
+    /// Number of public inputs the UltraHonk verifier reads from a packed
+    /// `[4-byte count][32-byte public inputs][proof]` blob, splitting it by
+    /// length the same way the verifier does
+    fn public_input_count(proof_blob: &Bytes) -> Option<u32> {
+        let rest = proof_blob.len().checked_sub(4)?;
+        ULTRAHONK_PROOF_FIELDS.iter().find_map(|proof_fields| {
+            let inputs_len = rest.checked_sub(proof_fields * 32)?;
+            (inputs_len % 32 == 0).then_some(inputs_len / 32)
+        })
+    }
+
+    /// The `index`-th public input of a packed UltraHonk blob. `None` if
+    /// `index` falls outside the public inputs the verifier reads, or the
+    /// count header disagrees with them.
+    fn public_input(proof_blob: &Bytes, index: u32) -> Option<Bytes> {
+        let count = Self::public_input_count(proof_blob)?;
+
+        let mut header = [0u8; 4];
+        proof_blob.slice(0..4).copy_into_slice(&mut header);
+        if u32::from_be_bytes(header) != count || index >= count {
+            return None;
+        }
+        let start = 4 + index * 32;
+        Some(proof_blob.slice(start..start + 32))
+    }
+
+    /// Verify the proof with the default verifier, mint on `nft_contract`
+    /// and confirm the mint landed
+    fn verify_and_mint(e: &Env, nft_contract: &Address, user: Address, expires_at: u64, vk_json: Bytes, proof_blob: Bytes) -> Result<u32, Error> {
+        let verifier_contract_address = Self::get_verifier_contract(e.clone());
+        let abi = Self::get_verifier_abi(e.clone());
+        Self::verify_and_mint_with(e, nft_contract, &verifier_contract_address, &abi, user, expires_at, vk_json, proof_blob)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn verify_and_mint_with(e: &Env, nft_contract: &Address, verifier_contract_address: &Address, abi: &VerifierAbi, user: Address, expires_at: u64, vk_json: Bytes, proof_blob: Bytes) -> Result<u32, Error> {
+        // Empty inputs would only trap inside the verifier; the key is not
+        // needed by verifiers that keep it on-chain
+        if proof_blob.is_empty() || (vk_json.is_empty() && abi.args != VerifierArgs::ProofOnly) {
+            return Err(Error::EmptyInput);
+        }
+
+        // Every minting path shares one replay set, so a proof that minted
+        // once cannot mint again through any entry point
+        let proof_key = DataKey::UsedProof(e.crypto().keccak256(&proof_blob).into());
+        if e.storage().persistent().has(&proof_key) {
+            return Err(Error::ProofAlreadyUsed);
+        }
+
+        // The proof must commit to the recipient, so it cannot be replayed
+        // to mint an ID for an unrelated address
+        if let Some(input_index) = Self::get_subject_binding(e.clone()) {
+            let committed_subject = Self::public_input(&proof_blob, input_index).ok_or(Error::SubjectMismatch)?;
+            if committed_subject != Bytes::from(Self::subject_hash(e.clone(), user.clone())) {
+                return Err(Error::SubjectMismatch);
+            }
+        }
+
+        // Fail fast on an already-passed expiration (0 means no expiration)
+        // before spending CPU on proof verification
+        if expires_at != 0 && expires_at <= e.ledger().timestamp() {
+            return Err(Error::InvalidExpiration);
+        }
+
+        // Throttle rapid re-minting by the same user
+        let cooldown_secs = Self::get_mint_cooldown(e.clone());
+        if cooldown_secs > 0 {
+            if let Some(last_mint) = Self::get_last_mint(e.clone(), user.clone()) {
+                if e.ledger().timestamp() < last_mint.saturating_add(cooldown_secs) {
+                    return Err(Error::CooldownActive);
+                }
+            }
+        }
+
+        // Cap issuance velocity across all users
+        let window_limit = Self::get_mint_window_limit(e.clone());
+        let (window_start, window_mints) = Self::current_mint_window(e, &window_limit);
+        if window_limit.max_mints > 0 && window_mints >= window_limit.max_mints {
+            return Err(Error::WindowLimitReached);
+        }
+
+        // Verify proof by calling the configured verify function (verify_proof on the ultrahonk verifier by default)
+        let verify_args = match abi.args {
+            VerifierArgs::VkThenProof => vec![e, vk_json.into_val(e), proof_blob.into_val(e)],
+            VerifierArgs::ProofThenVk => vec![e, proof_blob.into_val(e), vk_json.into_val(e)],
+            VerifierArgs::ProofOnly => vec![e, proof_blob.into_val(e)],
+        };
+        let verify_result: Val = e.invoke_contract(
+            verifier_contract_address,
+            &abi.function,
+            verify_args
+        );
+        let proof_id = Self::check_verify_result(e, verify_result, &proof_blob)?;
+
+        // Ask the collection which id the mint should get. This is the
+        // sequential counter rather than total_supply, which lags behind it
+        // once tokens are burned. Collections without the view skip the check.
+        let next_id_fn = Symbol::new(e, "next_token_id");
+        let expected_token_id = e.try_invoke_contract::<u32, InvokeError>(
+            nft_contract,
+            &next_id_fn,
+            vec![e]
+        );
+
+        // Proof verified successfully, call mint on the NFT contract with recipient and expiration
+        let mint_fn = Symbol::new(e, "mint");
+        let token_id: u32 = e.invoke_contract(
+            nft_contract,
+            &mint_fn,
+            vec![e, user.into_val(e), expires_at.into_val(e)]
+        );
+
+        if let Ok(Ok(expected_token_id)) = expected_token_id {
+            if token_id != expected_token_id {
+                return Err(Error::TokenIdMismatch);
+            }
+        }
+
+        // Read back ownership so a silently failed mint is not reported as success
+        let owner_fn = Symbol::new(e, "owner_of");
+        let minted_owner = e.try_invoke_contract::<Address, InvokeError>(
+            nft_contract,
+            &owner_fn,
+            vec![e, token_id.into_val(e)]
+        );
+        if !matches!(minted_owner, Ok(Ok(ref owner)) if *owner == user) {
+            return Err(Error::MintVerificationFailed);
+        }
+
+        // Keep the proof id so the token can be traced back to the proof that minted it
+        Self::set_record(e, &DataKey::TokenProof(nft_contract.clone(), token_id), &proof_id);
+        Self::set_record(e, &DataKey::TokenVerifier(nft_contract.clone(), token_id), verifier_contract_address);
+        Self::set_record(e, &proof_key, &true);
+
+        // Tell the verifier its proof was consumed, if it wants to know; a
+        // verifier without the callback does not block the mint
+        if let Some(consumed_fn) = Self::get_consumed_callback(e.clone()) {
+            let _ = e.try_invoke_contract::<(), InvokeError>(
+                verifier_contract_address,
+                &consumed_fn,
+                vec![e, proof_id.into_val(e), token_id.into_val(e)]
+            );
+        }
+
+        let total_minted = Self::get_total_minted(e.clone()) + 1;
+        e.storage().instance().set(&DataKey::TotalMinted, &total_minted);
+        Self::set_record(e, &DataKey::LastMint(user.clone()), &e.ledger().timestamp());
+        if window_limit.max_mints > 0 {
+            e.storage().instance().set(&DataKey::MintWindow, &(window_start, window_mints + 1));
+        }
+
+        // Notify the downstream hook, if one is configured
+        if let Some(hook) = Self::get_post_mint_hook(e.clone()) {
+            let on_mint_fn = Symbol::new(e, "on_mint");
+            let result = e.try_invoke_contract::<(), InvokeError>(
+                &hook,
+                &on_mint_fn,
+                vec![e, user.into_val(e), token_id.into_val(e)]
+            );
+            let strict: bool = e.storage().instance().get(&DataKey::PostMintHookStrict).unwrap_or(false);
+            if strict && !matches!(result, Ok(Ok(()))) {
+                return Err(Error::PostMintHookFailed);
+            }
+        }
+
+        Ok(token_id)
+    }
+
+    /// Configure a contract to be notified via `on_mint(user, token_id)` after
+    /// every successful mint. A failing hook only reverts the mint when `strict`.
+    pub fn set_post_mint_hook(e: Env, hook: Option<Address>, strict: bool) {
+        let admin = Self::require_admin(&e);
+
+        match &hook {
+            Some(hook) => e.storage().instance().set(&DataKey::PostMintHook, hook),
+            None => e.storage().instance().remove(&DataKey::PostMintHook),
+        }
+        e.storage().instance().set(&DataKey::PostMintHookStrict, &strict);
+
+        PostMintHookSet { admin, hook, strict }.publish(&e);
+    }
+
+    pub fn get_post_mint_hook(e: Env) -> Option<Address> {
+        e.storage().instance().get(&DataKey::PostMintHook)
+    }
+
+    /// Function called on the verifier as `function(proof_id, token_id)` after
+    /// each mint, e.g. `mark_consumed`. None disables the notification.
+    pub fn set_consumed_callback(e: Env, function: Option<Symbol>) {
+        let admin = Self::require_admin(&e);
+
+        match &function {
+            Some(function) => e.storage().instance().set(&DataKey::ConsumedCallback, function),
+            None => e.storage().instance().remove(&DataKey::ConsumedCallback),
+        }
+
+        ConsumedCallbackSet { admin, function }.publish(&e);
+    }
+
+    pub fn get_consumed_callback(e: Env) -> Option<Symbol> {
+        e.storage().instance().get(&DataKey::ConsumedCallback)
+    }
+
+    /// Allow at most `max_mints` mints per `window_secs`, across all users and
+    /// collections. `max_mints` of 0 disables the limit; an enabled limit
+    /// needs a non-zero window.
+    pub fn set_mint_window_limit(e: Env, max_mints: u32, window_secs: u64) -> Result<(), Error> {
+        let admin = Self::require_admin(&e);
+
+        // A zero-length window restarts on every mint and would never bind
+        if max_mints > 0 && window_secs == 0 {
+            return Err(Error::InvalidMintWindow);
+        }
+
+        let limit = MintWindowLimit { max_mints, window_secs };
+        e.storage().instance().set(&DataKey::MintWindowLimit, &limit);
+        // Start counting afresh under the new limit
+        e.storage().instance().remove(&DataKey::MintWindow);
+
+        MintWindowLimitSet { admin, limit }.publish(&e);
+        Ok(())
+    }
+
+    pub fn get_mint_window_limit(e: Env) -> MintWindowLimit {
+        e.storage()
+            .instance()
+            .get(&DataKey::MintWindowLimit)
+            .unwrap_or(MintWindowLimit { max_mints: 0, window_secs: 0 })
+    }
+
+    /// Require the proof's public input at `input_index` to equal
+    /// `subject_hash(user)` for the mint recipient. None disables the check.
+    pub fn set_subject_binding(e: Env, input_index: Option<u32>) {
+        let admin = Self::require_admin(&e);
+
+        match input_index {
+            Some(input_index) => e.storage().instance().set(&DataKey::SubjectInputIndex, &input_index),
+            None => e.storage().instance().remove(&DataKey::SubjectInputIndex),
+        }
+
+        SubjectBindingSet { admin, input_index }.publish(&e);
+    }
+
+    pub fn get_subject_binding(e: Env) -> Option<u32> {
+        e.storage().instance().get(&DataKey::SubjectInputIndex)
+    }
+
+    /// Value a proof commits to for `user`: sha256 of the address XDR with
+    /// the top byte cleared so it fits in a BN254 field element
+    pub fn subject_hash(e: Env, user: Address) -> BytesN<32> {
+        let mut hash = e.crypto().sha256(&user.to_xdr(&e)).to_array();
+        hash[0] = 0;
+        BytesN::from_array(&e, &hash)
+    }
+
+    /// Minimum seconds between two mints for the same user, across all
+    /// collections. 0 disables the cooldown.
+    pub fn set_mint_cooldown(e: Env, cooldown_secs: u64) {
+        let admin = Self::require_admin(&e);
+        e.storage().instance().set(&DataKey::MintCooldown, &cooldown_secs);
+
+        MintCooldownSet { admin, cooldown_secs }.publish(&e);
+    }
+
+    pub fn get_mint_cooldown(e: Env) -> u64 {
+        e.storage().instance().get(&DataKey::MintCooldown).unwrap_or(0)
+    }
+
+    /// Timestamp of the last mint issued to `user` through this handler
+    pub fn get_last_mint(e: Env, user: Address) -> Option<u64> {
+        Self::get_record(&e, &DataKey::LastMint(user))
+    }
+
+    /// Recover tokens accidentally sent to the handler, via the token's SAC interface
+    pub fn sweep(e: Env, admin: Address, token: Address, to: Address, amount: i128) -> Result<(), Error> {
+        if admin != Self::get_admin(e.clone()) {
+            return Err(Error::Unauthorized);
+        }
+        admin.require_auth();
+
+        token::TokenClient::new(&e, &token).transfer(&e.current_contract_address(), &to, &amount);
+
+        Swept { admin, token, to, amount }.publish(&e);
+        Ok(())
     }
 
     pub fn get_nft_balance(e: Env, user: Address) -> u32 {
@@ -70,6 +879,33 @@ impl InzpektorHandlerContract {
         balance
     }
 
+    /// Whether `user` holds at least one active INZPEKTOR-ID, i.e. one
+    /// that is past its valid-from time and not yet expired
+    pub fn has_valid_id(e: Env, user: Address) -> bool {
+        let inzpektor_id_contract_address = Self::get_nft_contract(e.clone());
+
+        let balance = Self::get_nft_balance(e.clone(), user.clone());
+        let owner_token_fn = Symbol::new(&e, "get_owner_token_id");
+        let is_active_fn = Symbol::new(&e, "is_active");
+        for index in 0..balance {
+            let token_id: u32 = e.invoke_contract(
+                &inzpektor_id_contract_address,
+                &owner_token_fn,
+                vec![&e, user.into_val(&e), index.into_val(&e)]
+            );
+            let is_active: bool = e.invoke_contract(
+                &inzpektor_id_contract_address,
+                &is_active_fn,
+                vec![&e, token_id.into_val(&e)]
+            );
+            if is_active {
+                return true;
+            }
+        }
+
+        false
+    }
+
     pub fn get_admin(e: Env) -> Address {
         e.storage().instance().get(&DataKey::Admin).expect("admin not set")
     }
@@ -82,6 +918,30 @@ impl InzpektorHandlerContract {
         e.storage().instance().get(&DataKey::InzpektorIDNFTContract).expect("NFT contract not set")
     }
 
+    /// Number of IDs issued through this handler, across all collections
+    pub fn get_total_minted(e: Env) -> u64 {
+        e.storage().instance().get(&DataKey::TotalMinted).unwrap_or(0)
+    }
+
+    /// Proof id returned by the verifier when `token_id` was minted on
+    /// `nft_contract` (the INZPEKTOR-ID or a credential collection)
+    pub fn get_token_proof(e: Env, nft_contract: Address, token_id: u32) -> Option<BytesN<32>> {
+        Self::get_record(&e, &DataKey::TokenProof(nft_contract, token_id))
+    }
+
+    /// Whether `token_id` on `nft_contract` was issued through this handler
+    /// rather than by another minter. Every handler mint records its proof
+    /// id, so that record doubles as the marker.
+    pub fn was_minted_here(e: Env, nft_contract: Address, token_id: u32) -> bool {
+        Self::get_token_proof(e, nft_contract, token_id).is_some()
+    }
+
+    /// Verifier contract that validated the proof for `token_id` on
+    /// `nft_contract`, as configured at its mint
+    pub fn get_token_verifier(e: Env, nft_contract: Address, token_id: u32) -> Option<Address> {
+        Self::get_record(&e, &DataKey::TokenVerifier(nft_contract, token_id))
+    }
+
     pub fn get_nft_owner(e: Env, token_id: u32) -> Address {
         let inzpektor_id_contract_address: Address = e.storage().instance().get(&DataKey::InzpektorIDNFTContract).expect("INZPEKTOR-ID contract not set");
 
@@ -1,11 +1,11 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::{testutils::Address as _, Address, Bytes, Env, String};
+use soroban_sdk::{testutils::{storage::Persistent as _, Address as _, Events, Ledger, MockAuth, MockAuthInvoke}, token, Address, Bytes, BytesN, Env, Event, IntoVal, String, Symbol};
 
 // Mock contracts for testing
 mod mock_verifier {
-    use soroban_sdk::{contract, contractimpl, Bytes, BytesN, Env};
+    use soroban_sdk::{contract, contractimpl, symbol_short, Bytes, BytesN, Env};
 
     #[contract]
     pub struct MockVerifier;
@@ -13,31 +13,117 @@ mod mock_verifier {
     #[contractimpl]
     impl MockVerifier {
         pub fn verify_proof(e: Env, _vk_json: Bytes, _proof_blob: Bytes) -> BytesN<32> {
+            e.storage().instance().set(&symbol_short!("called"), &true);
+
             // Return a dummy proof ID for testing
             BytesN::from_array(&e, &[1u8; 32])
         }
+
+        pub fn was_called(e: Env) -> bool {
+            e.storage().instance().get(&symbol_short!("called")).unwrap_or(false)
+        }
+
+        pub fn mark_consumed(e: Env, proof_id: BytesN<32>, token_id: u32) {
+            e.storage().instance().set(&symbol_short!("consumed"), &(proof_id, token_id));
+        }
+
+        pub fn last_consumed(e: Env) -> Option<(BytesN<32>, u32)> {
+            e.storage().instance().get(&symbol_short!("consumed"))
+        }
+    }
+}
+
+mod mock_groth16_verifier {
+    use soroban_sdk::{contract, contractimpl, Bytes, BytesN, Env};
+
+    #[contract]
+    pub struct MockGroth16Verifier;
+
+    // Verifier with a different ABI than the UltraHonk `verify_proof`
+    #[contractimpl]
+    impl MockGroth16Verifier {
+        pub fn verify(e: Env, proof: Bytes, vk: Bytes) -> BytesN<32> {
+            assert_eq!(proof.slice(..10), Bytes::from_slice(&e, b"mock_proof"));
+            assert_eq!(vk, Bytes::from_slice(&e, b"mock_vk"));
+            BytesN::from_array(&e, &[2u8; 32])
+        }
+
+        pub fn verify_with_stored_vk(e: Env, proof: Bytes) -> BytesN<32> {
+            assert_eq!(proof.slice(..10), Bytes::from_slice(&e, b"mock_proof"));
+            BytesN::from_array(&e, &[3u8; 32])
+        }
+    }
+}
+
+mod mock_rejecting_verifier {
+    use soroban_sdk::{contract, contractimpl, Bytes, BytesN, Env};
+
+    #[contract]
+    pub struct MockRejectingVerifier;
+
+    // Mirrors the UltraHonk verifier, which traps on an invalid proof
+    #[contractimpl]
+    impl MockRejectingVerifier {
+        pub fn verify_proof(_e: Env, _vk_json: Bytes, _proof_blob: Bytes) -> BytesN<32> {
+            panic!("invalid proof");
+        }
+    }
+}
+
+mod mock_status_verifier {
+    use soroban_sdk::{contract, contractimpl, symbol_short, Bytes, BytesN, Env};
+
+    #[contract]
+    pub struct MockStatusVerifier;
+
+    // Verifiers that report a status instead of trapping on invalid proofs
+    #[contractimpl]
+    impl MockStatusVerifier {
+        pub fn set_valid(e: Env, valid: bool) {
+            e.storage().instance().set(&symbol_short!("valid"), &valid);
+        }
+
+        pub fn verify_bool(e: Env, _vk_json: Bytes, _proof_blob: Bytes) -> bool {
+            e.storage().instance().get(&symbol_short!("valid")).unwrap_or(false)
+        }
+
+        pub fn verify_id(e: Env, _vk_json: Bytes, _proof_blob: Bytes) -> BytesN<32> {
+            let valid: bool = e.storage().instance().get(&symbol_short!("valid")).unwrap_or(false);
+            BytesN::from_array(&e, &[if valid { 4u8 } else { 0u8 }; 32])
+        }
+
+        pub fn verify_unexpected(_e: Env, _vk_json: Bytes, _proof_blob: Bytes) -> u32 {
+            1
+        }
     }
 }
 
 mod mock_nft {
-    use soroban_sdk::{contract, contractimpl, testutils::Address as _, Address, Env, String};
+    use soroban_sdk::{contract, contractimpl, symbol_short, testutils::Address as _, Address, Env, String};
 
     #[contract]
     pub struct MockNFT;
 
     #[contractimpl]
     impl MockNFT {
-        pub fn mint(_e: Env, _to: Address, _expires_at: u64) -> u32 {
-            // Mock mint - returns token_id 0
-            0
+        pub fn mint(e: Env, to: Address, _expires_at: u64) -> u32 {
+            // Mock mint - sequential token ids starting at 0
+            let token_id: u32 = e.storage().instance().get(&symbol_short!("next")).unwrap_or(0);
+            e.storage().instance().set(&token_id, &to);
+            e.storage().instance().set(&symbol_short!("next"), &(token_id + 1));
+            token_id
+        }
+
+        pub fn next_token_id(e: Env) -> u32 {
+            e.storage().instance().get(&symbol_short!("next")).unwrap_or(0)
         }
 
         pub fn balance(_e: Env, _account: Address) -> u32 {
             1
         }
 
-        pub fn owner_of(e: Env, _token_id: u32) -> Address {
-            Address::generate(&e)
+        pub fn owner_of(e: Env, token_id: u32) -> Address {
+            e.storage().instance().get(&token_id).unwrap_or_else(|| Address::generate(&e))
         }
 
         pub fn name(e: Env) -> String {
@@ -62,6 +148,176 @@ mod mock_nft {
     }
 }
 
+mod mock_drifting_nft {
+    use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env};
+
+    #[contract]
+    pub struct MockDriftingNFT;
+
+    // Reports the next id, but a concurrent mint takes it first
+    #[contractimpl]
+    impl MockDriftingNFT {
+        pub fn next_token_id(e: Env) -> u32 {
+            e.storage().instance().get(&symbol_short!("next")).unwrap_or(0)
+        }
+
+        pub fn mint(e: Env, to: Address, _expires_at: u64) -> u32 {
+            let token_id = Self::next_token_id(e.clone()) + 1;
+            e.storage().instance().set(&token_id, &to);
+            e.storage().instance().set(&symbol_short!("next"), &(token_id + 1));
+            token_id
+        }
+
+        pub fn owner_of(e: Env, token_id: u32) -> Address {
+            e.storage().instance().get(&token_id).unwrap()
+        }
+    }
+}
+
+mod mock_enumerable_nft {
+    use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, Vec};
+
+    #[contracttype]
+    enum Key {
+        Next,
+        Owner(u32),
+        Expiration(u32),
+        ValidFrom(u32),
+        OwnerTokens(Address),
+    }
+
+    #[contract]
+    pub struct MockEnumerableNFT;
+
+    // Tracks ownership and expiration per token, like the real INZPEKTOR-ID
+    #[contractimpl]
+    impl MockEnumerableNFT {
+        pub fn mint(e: Env, to: Address, expires_at: u64) -> u32 {
+            let token_id: u32 = e.storage().instance().get(&Key::Next).unwrap_or(0);
+            e.storage().instance().set(&Key::Next, &(token_id + 1));
+            e.storage().instance().set(&Key::Owner(token_id), &to);
+            e.storage().instance().set(&Key::Expiration(token_id), &expires_at);
+
+            let mut tokens = Self::owner_tokens(&e, &to);
+            tokens.push_back(token_id);
+            e.storage().instance().set(&Key::OwnerTokens(to), &tokens);
+
+            token_id
+        }
+
+        pub fn owner_of(e: Env, token_id: u32) -> Address {
+            e.storage().instance().get(&Key::Owner(token_id)).expect("token does not exist")
+        }
+
+        pub fn balance(e: Env, account: Address) -> u32 {
+            Self::owner_tokens(&e, &account).len()
+        }
+
+        pub fn get_owner_token_id(e: Env, owner: Address, index: u32) -> u32 {
+            Self::owner_tokens(&e, &owner).get(index).expect("index out of bounds")
+        }
+
+        pub fn get_expiration(e: Env, token_id: u32) -> u64 {
+            e.storage().instance().get(&Key::Expiration(token_id)).unwrap_or(0)
+        }
+
+        pub fn is_expired(e: Env, token_id: u32) -> bool {
+            let expiration = Self::get_expiration(e.clone(), token_id);
+            expiration != 0 && e.ledger().timestamp() > expiration
+        }
+
+        pub fn set_valid_from(e: Env, token_id: u32, valid_from: u64) {
+            e.storage().instance().set(&Key::ValidFrom(token_id), &valid_from);
+        }
+
+        pub fn is_active(e: Env, token_id: u32) -> bool {
+            let valid_from: u64 = e.storage().instance().get(&Key::ValidFrom(token_id)).unwrap_or(0);
+            e.ledger().timestamp() >= valid_from && !Self::is_expired(e, token_id)
+        }
+
+        fn owner_tokens(e: &Env, owner: &Address) -> Vec<u32> {
+            e.storage().instance().get(&Key::OwnerTokens(owner.clone())).unwrap_or(Vec::new(e))
+        }
+    }
+}
+
+mod mock_noop_nft {
+    use soroban_sdk::{contract, contractimpl, Address, Env};
+
+    #[contract]
+    pub struct MockNoopNFT;
+
+    #[contractimpl]
+    impl MockNoopNFT {
+        pub fn mint(_e: Env, _to: Address, _expires_at: u64) -> u32 {
+            // Mock mint that reports a token id but never records ownership
+            0
+        }
+
+        pub fn owner_of(_e: Env, _token_id: u32) -> Address {
+            panic!("token does not exist")
+        }
+    }
+}
+
+mod mock_hook {
+    use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env};
+
+    #[contract]
+    pub struct MockHook;
+
+    #[contractimpl]
+    impl MockHook {
+        pub fn on_mint(e: Env, user: Address, token_id: u32) {
+            // Record the last notification
+            e.storage().instance().set(&symbol_short!("last"), &(user, token_id));
+        }
+
+        pub fn last_mint(e: Env) -> Option<(Address, u32)> {
+            e.storage().instance().get(&symbol_short!("last"))
+        }
+    }
+}
+
+mod mock_failing_hook {
+    use soroban_sdk::{contract, contractimpl, Address, Env};
+
+    #[contract]
+    pub struct MockFailingHook;
+
+    #[contractimpl]
+    impl MockFailingHook {
+        pub fn on_mint(_e: Env, _user: Address, _token_id: u32) {
+            panic!("hook failure")
+        }
+    }
+}
+
+fn assert_emitted(env: &Env, contract_id: &Address, event: impl Event) {
+    let expected = (contract_id.clone(), event.topics(env), event.data(env));
+    assert!(env.events().all().contains(expected));
+}
+
+// Distinct proof blobs, since each proof can only mint once
+fn mock_proof(env: &Env, n: u8) -> Bytes {
+    let mut proof_blob = Bytes::from_slice(env, b"mock_proof");
+    proof_blob.push_back(n);
+    proof_blob
+}
+
+// Pack `[count][32-byte public inputs][proof]` the way the UltraHonk verifier
+// expects it, with a dummy proof section of `proof_fields` 32-byte fields
+fn packed_proof(env: &Env, header_count: u32, inputs: &[Bytes], proof_fields: u32) -> Bytes {
+    let mut blob = Bytes::from_array(env, &header_count.to_be_bytes());
+    for input in inputs {
+        blob.append(input);
+    }
+    for _ in 0..proof_fields {
+        blob.extend_from_array(&[9u8; 32]);
+    }
+    blob
+}
+
 // Helper function to initialize using the client
 fn setup_contract_storage(client: &InzpektorHandlerContractClient, admin: &Address, verifier: &Address, nft: &Address) {
     client.initialize(admin, verifier, nft);
@@ -215,3 +471,1207 @@ fn test_get_nft_expiration() {
     let is_expired = client.is_nft_expired(&token_id);
     assert_eq!(is_expired, false);
 }
+
+#[test]
+fn test_mint_verification_failed() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let nft_contract = env.register(mock_noop_nft::MockNoopNFT, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+
+    let vk_json = Bytes::from_slice(&env, b"mock_vk");
+    let proof_blob = Bytes::from_slice(&env, b"mock_proof");
+    let expires_at = env.ledger().timestamp() + 365 * 24 * 60 * 60;
+
+    // The NFT contract reports a token id but never assigns it to the user
+    let result = client.try_mint_inzpektor_id(&user, &expires_at, &vk_json, &proof_blob);
+    assert_eq!(result, Err(Ok(Error::MintVerificationFailed)));
+}
+
+#[test]
+fn test_mint_credential_types() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let inspector = Address::generate(&env);
+    let auditor = Address::generate(&env);
+    let verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let nft_contract = env.register(mock_nft::MockNFT, ());
+    let inspector_nft = env.register(mock_nft::MockNFT, ());
+    let auditor_nft = env.register(mock_nft::MockNFT, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+
+    let inspector_type = Symbol::new(&env, "inspector");
+    let auditor_type = Symbol::new(&env, "auditor");
+    client.set_credential_collection(&inspector_type, &inspector_nft);
+    client.set_credential_collection(&auditor_type, &auditor_nft);
+    assert_eq!(client.get_credential_collection(&inspector_type), Some(inspector_nft.clone()));
+    assert_eq!(client.get_credential_collection(&auditor_type), Some(auditor_nft.clone()));
+
+    let vk_json = Bytes::from_slice(&env, b"mock_vk");
+    let expires_at = env.ledger().timestamp() + 365 * 24 * 60 * 60;
+
+    // Each credential type is minted on its own collection
+    let inspector_token = client.mint_credential(&inspector, &expires_at, &vk_json, &mock_proof(&env, 0), &inspector_type);
+    let auditor_token = client.mint_credential(&auditor, &expires_at, &vk_json, &mock_proof(&env, 1), &auditor_type);
+
+    let inspector_client = mock_nft::MockNFTClient::new(&env, &inspector_nft);
+    let auditor_client = mock_nft::MockNFTClient::new(&env, &auditor_nft);
+    assert_eq!(inspector_client.owner_of(&inspector_token), inspector);
+    assert_eq!(auditor_client.owner_of(&auditor_token), auditor);
+
+    // Their proof records are kept per collection
+    assert_eq!(client.get_token_proof(&auditor_nft, &auditor_token), Some(BytesN::from_array(&env, &[1u8; 32])));
+    assert_eq!(client.get_token_verifier(&inspector_nft, &inspector_token), Some(verifier_contract.clone()));
+    assert!(client.was_minted_here(&inspector_nft, &inspector_token));
+    assert!(!client.was_minted_here(&nft_contract, &inspector_token));
+
+    // Unregistered credential types are rejected
+    client.remove_credential_collection(&auditor_type);
+    assert_eq!(client.get_credential_collection(&auditor_type), None);
+    let result = client.try_mint_credential(&auditor, &expires_at, &vk_json, &mock_proof(&env, 2), &auditor_type);
+    assert_eq!(result, Err(Ok(Error::UnknownCredentialType)));
+}
+
+#[test]
+fn test_admin_audit_events() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let nft_contract = env.register(mock_nft::MockNFT, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+    assert_emitted(&env, &contract_id, Initialized {
+        admin: admin.clone(),
+        verifier_contract: verifier_contract.clone(),
+        nft_contract: nft_contract.clone(),
+    });
+
+    let new_verifier = env.register(mock_verifier::MockVerifier, ());
+    client.set_verifier_contract(&new_verifier);
+    assert_emitted(&env, &contract_id, VerifierContractSet {
+        admin: admin.clone(),
+        verifier_contract: new_verifier.clone(),
+    });
+    assert_eq!(client.get_verifier_contract(), new_verifier);
+
+    let new_nft = env.register(mock_nft::MockNFT, ());
+    client.set_nft_contract(&new_nft);
+    assert_emitted(&env, &contract_id, NftContractSet {
+        admin: admin.clone(),
+        nft_contract: new_nft.clone(),
+    });
+    assert_eq!(client.get_nft_contract(), new_nft);
+
+    let credential_type = Symbol::new(&env, "auditor");
+    client.set_credential_collection(&credential_type, &new_nft);
+    assert_emitted(&env, &contract_id, CredentialCollectionSet {
+        admin: admin.clone(),
+        credential_type: credential_type.clone(),
+        nft_contract: new_nft,
+    });
+
+    client.remove_credential_collection(&credential_type);
+    assert_emitted(&env, &contract_id, CredentialCollectionRemoved { admin, credential_type });
+}
+
+#[test]
+fn test_sweep() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let nft_contract = env.register(mock_nft::MockNFT, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+
+    // Fund the handler with a stray token balance
+    let issuer = Address::generate(&env);
+    let stray_token = env.register_stellar_asset_contract_v2(issuer).address();
+    token::StellarAssetClient::new(&env, &stray_token).mint(&contract_id, &1_000);
+
+    client.sweep(&admin, &stray_token, &recipient, &600);
+    assert_emitted(&env, &contract_id, Swept {
+        admin: admin.clone(),
+        token: stray_token.clone(),
+        to: recipient.clone(),
+        amount: 600,
+    });
+
+    let token_client = token::TokenClient::new(&env, &stray_token);
+    assert_eq!(token_client.balance(&recipient), 600);
+    assert_eq!(token_client.balance(&contract_id), 400);
+
+    // Only the stored admin may sweep
+    let result = client.try_sweep(&recipient, &stray_token, &recipient, &400);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+}
+
+#[test]
+fn test_post_mint_hook() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let nft_contract = env.register(mock_nft::MockNFT, ());
+    let hook = env.register(mock_hook::MockHook, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+    assert_eq!(client.get_post_mint_hook(), None);
+
+    client.set_post_mint_hook(&Some(hook.clone()), &false);
+    assert_eq!(client.get_post_mint_hook(), Some(hook.clone()));
+
+    let vk_json = Bytes::from_slice(&env, b"mock_vk");
+    let expires_at = env.ledger().timestamp() + 365 * 24 * 60 * 60;
+
+    let token_id = client.mint_inzpektor_id(&user, &expires_at, &vk_json, &mock_proof(&env, 0));
+
+    // The hook was notified with the minted token
+    let hook_client = mock_hook::MockHookClient::new(&env, &hook);
+    assert_eq!(hook_client.last_mint(), Some((user.clone(), token_id)));
+
+    // Removing the hook stops notifications
+    client.set_post_mint_hook(&None, &false);
+    assert_eq!(client.get_post_mint_hook(), None);
+
+    let other_user = Address::generate(&env);
+    client.mint_inzpektor_id(&other_user, &expires_at, &vk_json, &mock_proof(&env, 1));
+    assert_eq!(hook_client.last_mint(), Some((user, token_id)));
+}
+
+#[test]
+fn test_failing_post_mint_hook() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let nft_contract = env.register(mock_nft::MockNFT, ());
+    let hook = env.register(mock_failing_hook::MockFailingHook, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+
+    let vk_json = Bytes::from_slice(&env, b"mock_vk");
+    let expires_at = env.ledger().timestamp() + 365 * 24 * 60 * 60;
+
+    // A failing hook is tolerated by default
+    client.set_post_mint_hook(&Some(hook.clone()), &false);
+    let token_id = client.mint_inzpektor_id(&user, &expires_at, &vk_json, &mock_proof(&env, 0));
+    assert_eq!(token_id, 0);
+
+    // In strict mode it reverts the mint
+    client.set_post_mint_hook(&Some(hook), &true);
+    let result = client.try_mint_inzpektor_id(&user, &expires_at, &vk_json, &mock_proof(&env, 1));
+    assert_eq!(result, Err(Ok(Error::PostMintHookFailed)));
+}
+
+#[test]
+fn test_get_token_proof() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let nft_contract = env.register(mock_nft::MockNFT, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+
+    let vk_json = Bytes::from_slice(&env, b"mock_vk");
+    let proof_blob = Bytes::from_slice(&env, b"mock_proof");
+    let expires_at = env.ledger().timestamp() + 365 * 24 * 60 * 60;
+
+    let token_id = client.mint_inzpektor_id(&user, &expires_at, &vk_json, &proof_blob);
+
+    // The mock verifier always returns [1; 32] as the proof id
+    assert_eq!(client.get_token_proof(&nft_contract, &token_id), Some(BytesN::from_array(&env, &[1u8; 32])));
+
+    // Tokens not minted through the handler have no proof on record
+    assert_eq!(client.get_token_proof(&nft_contract, &(token_id + 1)), None);
+}
+
+#[test]
+fn test_get_token_verifier() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let new_verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let nft_contract = env.register(mock_nft::MockNFT, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+
+    let vk_json = Bytes::from_slice(&env, b"mock_vk");
+    let expires_at = env.ledger().timestamp() + 365 * 24 * 60 * 60;
+
+    let first_token = client.mint_inzpektor_id(&user, &expires_at, &vk_json, &mock_proof(&env, 0));
+
+    client.set_verifier_contract(&new_verifier_contract);
+    let second_token = client.mint_inzpektor_id(&user, &expires_at, &vk_json, &mock_proof(&env, 1));
+
+    // Each token keeps the verifier active at its mint
+    assert_eq!(client.get_token_verifier(&nft_contract, &first_token), Some(verifier_contract));
+    assert_eq!(client.get_token_verifier(&nft_contract, &second_token), Some(new_verifier_contract));
+    assert_eq!(client.get_token_verifier(&nft_contract, &(second_token + 1)), None);
+}
+
+#[test]
+fn test_was_minted_here() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let nft_contract = env.register(mock_nft::MockNFT, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+
+    let vk_json = Bytes::from_slice(&env, b"mock_vk");
+    let proof_blob = Bytes::from_slice(&env, b"mock_proof");
+
+    let handler_token = client.mint_inzpektor_id(&user, &0, &vk_json, &proof_blob);
+
+    // Another minter issuing directly on the NFT
+    let direct_token = mock_nft::MockNFTClient::new(&env, &nft_contract).mint(&user, &0);
+
+    assert!(client.was_minted_here(&nft_contract, &handler_token));
+    assert!(!client.was_minted_here(&nft_contract, &direct_token));
+}
+
+#[test]
+fn test_two_step_admin_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+    let intruder = Address::generate(&env);
+    let verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let nft_contract = env.register(mock_nft::MockNFT, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+
+    // Nothing to accept yet
+    assert_eq!(client.try_accept_admin(&new_admin), Err(Ok(Error::NoPendingAdmin)));
+
+    // Only the current admin can propose
+    assert_eq!(client.try_propose_admin(&intruder, &intruder), Err(Ok(Error::Unauthorized)));
+
+    client.propose_admin(&admin, &new_admin);
+    assert_emitted(&env, &contract_id, AdminProposed { admin: admin.clone(), new_admin: new_admin.clone() });
+    assert_eq!(client.get_pending_admin(), Some(new_admin.clone()));
+
+    // The admin does not change until the proposal is accepted
+    assert_eq!(client.get_admin(), admin);
+
+    // Only the pending admin can accept
+    assert_eq!(client.try_accept_admin(&intruder), Err(Ok(Error::Unauthorized)));
+
+    client.accept_admin(&new_admin);
+    assert_emitted(&env, &contract_id, AdminAccepted { previous_admin: admin, admin: new_admin.clone() });
+    assert_eq!(client.get_admin(), new_admin);
+    assert_eq!(client.get_pending_admin(), None);
+}
+
+#[test]
+fn test_past_expiration_rejected_before_verification() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let nft_contract = env.register(mock_nft::MockNFT, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+
+    env.ledger().set_timestamp(10_000);
+
+    let vk_json = Bytes::from_slice(&env, b"mock_vk");
+    let proof_blob = Bytes::from_slice(&env, b"mock_proof");
+
+    // Past and current timestamps are rejected without calling the verifier
+    let result = client.try_mint_inzpektor_id(&user, &5_000, &vk_json, &proof_blob);
+    assert_eq!(result, Err(Ok(Error::InvalidExpiration)));
+    let result = client.try_mint_inzpektor_id(&user, &10_000, &vk_json, &proof_blob);
+    assert_eq!(result, Err(Ok(Error::InvalidExpiration)));
+
+    let verifier_client = mock_verifier::MockVerifierClient::new(&env, &verifier_contract);
+    assert!(!verifier_client.was_called());
+
+    // No expiration (0) is still accepted
+    client.mint_inzpektor_id(&user, &0, &vk_json, &proof_blob);
+    assert!(verifier_client.was_called());
+}
+
+#[test]
+fn test_total_minted() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let nft_contract = env.register(mock_nft::MockNFT, ());
+    let auditor_nft = env.register(mock_nft::MockNFT, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+    assert_eq!(client.get_total_minted(), 0);
+
+    let vk_json = Bytes::from_slice(&env, b"mock_vk");
+    let expires_at = env.ledger().timestamp() + 365 * 24 * 60 * 60;
+
+    client.mint_inzpektor_id(&Address::generate(&env), &expires_at, &vk_json, &mock_proof(&env, 0));
+    client.mint_inzpektor_id(&Address::generate(&env), &expires_at, &vk_json, &mock_proof(&env, 1));
+    assert_eq!(client.get_total_minted(), 2);
+
+    // Mints into other credential collections count too
+    let auditor_type = Symbol::new(&env, "auditor");
+    client.set_credential_collection(&auditor_type, &auditor_nft);
+    client.mint_credential(&Address::generate(&env), &expires_at, &vk_json, &mock_proof(&env, 2), &auditor_type);
+    assert_eq!(client.get_total_minted(), 3);
+}
+
+#[test]
+fn test_has_valid_id() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let valid_holder = Address::generate(&env);
+    let expired_holder = Address::generate(&env);
+    let non_holder = Address::generate(&env);
+    let verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let nft_contract = env.register(mock_enumerable_nft::MockEnumerableNFT, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+
+    env.ledger().set_timestamp(10_000);
+
+    let vk_json = Bytes::from_slice(&env, b"mock_vk");
+
+    // The valid holder has an expired token and a long-lived one
+    client.mint_inzpektor_id(&valid_holder, &20_000, &vk_json, &mock_proof(&env, 0));
+    client.mint_inzpektor_id(&valid_holder, &100_000, &vk_json, &mock_proof(&env, 1));
+    client.mint_inzpektor_id(&expired_holder, &20_000, &vk_json, &mock_proof(&env, 2));
+
+    env.ledger().set_timestamp(30_000);
+
+    assert!(client.has_valid_id(&valid_holder));
+    assert!(!client.has_valid_id(&expired_holder));
+    assert!(!client.has_valid_id(&non_holder));
+}
+
+#[test]
+fn test_has_valid_id_ignores_pending_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let nft_contract = env.register(mock_enumerable_nft::MockEnumerableNFT, ());
+    let nft_client = mock_enumerable_nft::MockEnumerableNFTClient::new(&env, &nft_contract);
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+
+    env.ledger().set_timestamp(10_000);
+
+    let vk_json = Bytes::from_slice(&env, b"mock_vk");
+    let proof_blob = Bytes::from_slice(&env, b"mock_proof");
+
+    let token_id = client.mint_inzpektor_id(&user, &100_000, &vk_json, &proof_blob);
+    nft_client.set_valid_from(&token_id, &50_000);
+
+    // Unexpired but not yet valid
+    assert!(!client.has_valid_id(&user));
+
+    env.ledger().set_timestamp(50_000);
+    assert!(client.has_valid_id(&user));
+}
+
+#[test]
+fn test_mint_with_consent() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let nft_contract = env.register(mock_nft::MockNFT, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+
+    let vk_json = Bytes::from_slice(&env, b"mock_vk");
+    let proof_blob = Bytes::from_slice(&env, b"mock_proof");
+    let expires_at = env.ledger().timestamp() + 365 * 24 * 60 * 60;
+    let proof_hash: BytesN<32> = env.crypto().keccak256(&proof_blob).into();
+
+    let admin_auth = MockAuth {
+        address: &admin,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "mint_with_consent",
+            args: (user.clone(), expires_at, vk_json.clone(), proof_blob.clone()).into_val(&env),
+            sub_invokes: &[],
+        },
+    };
+
+    // The user signed consent for a different expiration
+    let forged_args = (expires_at + 1, proof_hash.clone()).into_val(&env);
+    let forged_consent = MockAuth {
+        address: &user,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "mint_with_consent",
+            args: forged_args,
+            sub_invokes: &[],
+        },
+    };
+    let result = client
+        .mock_auths(&[admin_auth.clone(), forged_consent])
+        .try_mint_with_consent(&user, &expires_at, &vk_json, &proof_blob);
+    assert!(result.is_err());
+
+    // The user's consent covers exactly this expiration and proof
+    let consent_args = (expires_at, proof_hash).into_val(&env);
+    let consent = MockAuth {
+        address: &user,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "mint_with_consent",
+            args: consent_args,
+            sub_invokes: &[],
+        },
+    };
+    let token_id = client
+        .mock_auths(&[admin_auth, consent])
+        .mint_with_consent(&user, &expires_at, &vk_json, &proof_blob);
+
+    let nft_client = mock_nft::MockNFTClient::new(&env, &nft_contract);
+    assert_eq!(nft_client.owner_of(&token_id), user);
+}
+
+#[test]
+fn test_configurable_verifier_abi() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let verifier_contract = env.register(mock_groth16_verifier::MockGroth16Verifier, ());
+    let nft_contract = env.register(mock_nft::MockNFT, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+
+    // Defaults to the UltraHonk ABI
+    assert_eq!(client.get_verifier_abi(), VerifierAbi {
+        function: Symbol::new(&env, "verify_proof"),
+        args: VerifierArgs::VkThenProof,
+    });
+
+    let vk_json = Bytes::from_slice(&env, b"mock_vk");
+    let expires_at = env.ledger().timestamp() + 365 * 24 * 60 * 60;
+
+    // The mock has no verify_proof function
+    assert!(client.try_mint_inzpektor_id(&user, &expires_at, &vk_json, &mock_proof(&env, 0)).is_err());
+
+    let verify_fn = Symbol::new(&env, "verify");
+    client.set_verifier_abi(&verify_fn, &VerifierArgs::ProofThenVk);
+    assert_emitted(&env, &contract_id, VerifierAbiSet {
+        admin: admin.clone(),
+        abi: VerifierAbi { function: verify_fn, args: VerifierArgs::ProofThenVk },
+    });
+
+    let token_id = client.mint_inzpektor_id(&user, &expires_at, &vk_json, &mock_proof(&env, 0));
+    assert_eq!(client.get_token_proof(&nft_contract, &token_id), Some(BytesN::from_array(&env, &[2u8; 32])));
+
+    // Proof-only verifiers get just the proof blob
+    client.set_verifier_abi(&Symbol::new(&env, "verify_with_stored_vk"), &VerifierArgs::ProofOnly);
+    let token_id = client.mint_inzpektor_id(&user, &expires_at, &vk_json, &mock_proof(&env, 1));
+    assert_eq!(client.get_token_proof(&nft_contract, &token_id), Some(BytesN::from_array(&env, &[3u8; 32])));
+}
+
+#[test]
+fn test_mint_cooldown() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let other_user = Address::generate(&env);
+    let verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let nft_contract = env.register(mock_nft::MockNFT, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+
+    let one_day: u64 = 24 * 60 * 60;
+    client.set_mint_cooldown(&one_day);
+    assert_emitted(&env, &contract_id, MintCooldownSet { admin, cooldown_secs: one_day });
+    assert_eq!(client.get_mint_cooldown(), one_day);
+
+    env.ledger().set_timestamp(10_000);
+
+    let vk_json = Bytes::from_slice(&env, b"mock_vk");
+
+    client.mint_inzpektor_id(&user, &0, &vk_json, &mock_proof(&env, 0));
+    assert_eq!(client.get_last_mint(&user), Some(10_000));
+
+    // An immediate re-mint is rejected
+    let result = client.try_mint_inzpektor_id(&user, &0, &vk_json, &mock_proof(&env, 1));
+    assert_eq!(result, Err(Ok(Error::CooldownActive)));
+
+    // Other users are not affected
+    client.mint_inzpektor_id(&other_user, &0, &vk_json, &mock_proof(&env, 2));
+
+    env.ledger().set_timestamp(10_000 + one_day - 1);
+    let result = client.try_mint_inzpektor_id(&user, &0, &vk_json, &mock_proof(&env, 1));
+    assert_eq!(result, Err(Ok(Error::CooldownActive)));
+
+    // Allowed again once the cooldown has elapsed
+    env.ledger().set_timestamp(10_000 + one_day);
+    client.mint_inzpektor_id(&user, &0, &vk_json, &mock_proof(&env, 1));
+    assert_eq!(client.get_last_mint(&user), Some(10_000 + one_day));
+}
+
+#[test]
+fn test_self_mint() {
+    let env = Env::default();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let rejecting_verifier = env.register(mock_rejecting_verifier::MockRejectingVerifier, ());
+    let nft_contract = env.register(mock_nft::MockNFT, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+
+    let vk_json = Bytes::from_slice(&env, b"mock_vk");
+    let subject: Bytes = client.subject_hash(&user).into();
+    let proof_blob = packed_proof(&env, 1, core::slice::from_ref(&subject), 456);
+
+    // Disabled by default
+    assert_eq!(client.try_self_mint(&user, &vk_json, &proof_blob), Err(Ok(Error::SelfMintDisabled)));
+
+    // Proofs must be bound to their user before self-minting can be enabled
+    let one_year: u64 = 365 * 24 * 60 * 60;
+    let vk_hash: BytesN<32> = env.crypto().keccak256(&vk_json).into();
+    let result = client.try_set_self_mint(&true, &one_year, &vk_hash);
+    assert_eq!(result, Err(Ok(Error::SubjectBindingRequired)));
+
+    client.set_subject_binding(&Some(0));
+    client.set_self_mint(&true, &one_year, &vk_hash);
+    let config = SelfMintConfig { enabled: true, validity_secs: one_year, vk_hash };
+    assert_emitted(&env, &contract_id, SelfMintSet { admin, config: config.clone() });
+    assert_eq!(client.get_self_mint(), Some(config));
+
+    // Someone else cannot self-mint on the user's behalf
+    let intruder = Address::generate(&env);
+    env.mock_auths(&[MockAuth {
+        address: &intruder,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "self_mint",
+            args: (&user, &vk_json, &proof_blob).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    let result = client.try_self_mint(&user, &vk_json, &proof_blob);
+    assert!(result.is_err());
+
+    // Only the user's own authorization is needed
+    env.mock_auths(&[MockAuth {
+        address: &user,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "self_mint",
+            args: (&user, &vk_json, &proof_blob).into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    let token_id = client.self_mint(&user, &vk_json, &proof_blob);
+    assert_eq!(client.get_nft_owner(&token_id), user);
+    assert_eq!(client.get_total_minted(), 1);
+
+    // An invalid proof is rejected by the verifier
+    env.mock_all_auths();
+    let fresh_proof = packed_proof(&env, 2, &[subject.clone(), Bytes::from_array(&env, &[1u8; 32])], 456);
+    client.set_verifier_contract(&rejecting_verifier);
+    let result = client.try_self_mint(&user, &vk_json, &fresh_proof);
+    assert!(result.is_err());
+    assert_eq!(client.get_total_minted(), 1);
+
+    // Removing the binding later stops self-minting too
+    client.set_verifier_contract(&verifier_contract);
+    client.set_subject_binding(&None);
+    let result = client.try_self_mint(&user, &vk_json, &fresh_proof);
+    assert_eq!(result, Err(Ok(Error::SubjectBindingRequired)));
+}
+
+#[test]
+fn test_self_mint_rejects_foreign_vk() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let nft_contract = env.register(mock_nft::MockNFT, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+    let verifier_client = mock_verifier::MockVerifierClient::new(&env, &verifier_contract);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+
+    let vk_json = Bytes::from_slice(&env, b"mock_vk");
+    client.set_subject_binding(&Some(0));
+    client.set_self_mint(&true, &0, &env.crypto().keccak256(&vk_json).into());
+
+    // A proof against the user's own circuit is rejected before verification
+    let foreign_vk = Bytes::from_slice(&env, b"trivial_circuit_vk");
+    let proof_blob = packed_proof(&env, 1, &[client.subject_hash(&user).into()], 456);
+    let result = client.try_self_mint(&user, &foreign_vk, &proof_blob);
+    assert_eq!(result, Err(Ok(Error::VkMismatch)));
+    assert!(!verifier_client.was_called());
+    assert_eq!(client.get_total_minted(), 0);
+
+    client.self_mint(&user, &vk_json, &proof_blob);
+    assert_eq!(client.get_total_minted(), 1);
+}
+
+#[test]
+fn test_self_mint_rejects_replayed_proof() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let other_user = Address::generate(&env);
+    let verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let nft_contract = env.register(mock_nft::MockNFT, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+
+    let vk_json = Bytes::from_slice(&env, b"mock_vk");
+    client.set_subject_binding(&Some(0));
+    client.set_self_mint(&true, &0, &env.crypto().keccak256(&vk_json).into());
+
+    let proof_blob = packed_proof(&env, 1, &[client.subject_hash(&user).into()], 456);
+    client.self_mint(&user, &vk_json, &proof_blob);
+
+    // The same proof cannot mint again, for the same user or anyone else
+    let result = client.try_self_mint(&user, &vk_json, &proof_blob);
+    assert_eq!(result, Err(Ok(Error::ProofAlreadyUsed)));
+    let result = client.try_self_mint(&other_user, &vk_json, &proof_blob);
+    assert_eq!(result, Err(Ok(Error::ProofAlreadyUsed)));
+    assert_eq!(client.get_total_minted(), 1);
+
+    // The used-proof record lives in persistent storage
+    let proof_key = DataKey::UsedProof(env.crypto().keccak256(&proof_blob).into());
+    assert!(env.as_contract(&contract_id, || env.storage().persistent().has(&proof_key)));
+
+    let other_proof = packed_proof(&env, 1, &[client.subject_hash(&other_user).into()], 456);
+    client.self_mint(&other_user, &vk_json, &other_proof);
+    assert_eq!(client.get_total_minted(), 2);
+}
+
+#[test]
+fn test_admin_minted_proof_cannot_be_self_minted() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let attacker = Address::generate(&env);
+    let verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let nft_contract = env.register(mock_nft::MockNFT, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+
+    let vk_json = Bytes::from_slice(&env, b"mock_vk");
+    client.set_subject_binding(&Some(0));
+    client.set_self_mint(&true, &0, &env.crypto().keccak256(&vk_json).into());
+
+    // The admin mints for the user; the proof is now public in the transaction
+    let proof_blob = packed_proof(&env, 1, &[client.subject_hash(&user).into()], 456);
+    client.mint_inzpektor_id(&user, &0, &vk_json, &proof_blob);
+
+    // It cannot be reused through self_mint, by anyone
+    let result = client.try_self_mint(&attacker, &vk_json, &proof_blob);
+    assert_eq!(result, Err(Ok(Error::ProofAlreadyUsed)));
+    let result = client.try_self_mint(&user, &vk_json, &proof_blob);
+    assert_eq!(result, Err(Ok(Error::ProofAlreadyUsed)));
+
+    // Nor through any other admin path
+    let result = client.try_mint_inzpektor_id(&user, &0, &vk_json, &proof_blob);
+    assert_eq!(result, Err(Ok(Error::ProofAlreadyUsed)));
+    assert_eq!(client.get_total_minted(), 1);
+}
+
+#[test]
+fn test_token_id_drift_detected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let nft_contract = env.register(mock_nft::MockNFT, ());
+    let drifting_nft = env.register(mock_drifting_nft::MockDriftingNFT, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+
+    let vk_json = Bytes::from_slice(&env, b"mock_vk");
+
+    // Ids match the collection's counter
+    assert_eq!(client.mint_inzpektor_id(&user, &0, &vk_json, &mock_proof(&env, 0)), 0);
+    assert_eq!(client.mint_inzpektor_id(&user, &0, &vk_json, &mock_proof(&env, 1)), 1);
+
+    // A concurrent mint shifts the id
+    client.set_nft_contract(&drifting_nft);
+    let result = client.try_mint_inzpektor_id(&user, &0, &vk_json, &mock_proof(&env, 2));
+    assert_eq!(result, Err(Ok(Error::TokenIdMismatch)));
+    assert_eq!(client.get_total_minted(), 2);
+}
+
+#[test]
+fn test_consumed_callback() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let nft_contract = env.register(mock_nft::MockNFT, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+    let verifier_client = mock_verifier::MockVerifierClient::new(&env, &verifier_contract);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+
+    let vk_json = Bytes::from_slice(&env, b"mock_vk");
+
+    // Not notified by default
+    client.mint_inzpektor_id(&user, &0, &vk_json, &mock_proof(&env, 0));
+    assert_eq!(verifier_client.last_consumed(), None);
+
+    let mark_consumed = Symbol::new(&env, "mark_consumed");
+    client.set_consumed_callback(&Some(mark_consumed.clone()));
+    assert_emitted(&env, &contract_id, ConsumedCallbackSet { admin: admin.clone(), function: Some(mark_consumed.clone()) });
+    assert_eq!(client.get_consumed_callback(), Some(mark_consumed));
+
+    let token_id = client.mint_inzpektor_id(&user, &0, &vk_json, &mock_proof(&env, 1));
+    assert_eq!(verifier_client.last_consumed(), Some((BytesN::from_array(&env, &[1u8; 32]), token_id)));
+
+    // A verifier without the callback does not block minting
+    client.set_consumed_callback(&Some(Symbol::new(&env, "not_implemented")));
+    let token_id = client.mint_inzpektor_id(&user, &0, &vk_json, &mock_proof(&env, 2));
+    assert_eq!(client.get_nft_owner(&token_id), user);
+
+    client.set_consumed_callback(&None);
+    assert_eq!(client.get_consumed_callback(), None);
+}
+
+#[test]
+fn test_mint_window_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let nft_contract = env.register(mock_nft::MockNFT, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+
+    // A limit without a window would never be enforced
+    assert_eq!(client.try_set_mint_window_limit(&2, &0), Err(Ok(Error::InvalidMintWindow)));
+    assert_eq!(client.get_mint_window_limit(), MintWindowLimit { max_mints: 0, window_secs: 0 });
+
+    let one_hour: u64 = 60 * 60;
+    client.set_mint_window_limit(&2, &one_hour);
+    let limit = MintWindowLimit { max_mints: 2, window_secs: one_hour };
+    assert_emitted(&env, &contract_id, MintWindowLimitSet { admin, limit });
+    assert_eq!(client.get_mint_window_limit(), limit);
+
+    env.ledger().set_timestamp(10_000);
+
+    let vk_json = Bytes::from_slice(&env, b"mock_vk");
+
+    client.mint_inzpektor_id(&Address::generate(&env), &0, &vk_json, &mock_proof(&env, 0));
+    env.ledger().set_timestamp(10_000 + one_hour / 2);
+    client.mint_inzpektor_id(&Address::generate(&env), &0, &vk_json, &mock_proof(&env, 1));
+
+    // The cap applies to every user
+    let result = client.try_mint_inzpektor_id(&Address::generate(&env), &0, &vk_json, &mock_proof(&env, 2));
+    assert_eq!(result, Err(Ok(Error::WindowLimitReached)));
+
+    env.ledger().set_timestamp(10_000 + one_hour - 1);
+    let result = client.try_mint_inzpektor_id(&Address::generate(&env), &0, &vk_json, &mock_proof(&env, 3));
+    assert_eq!(result, Err(Ok(Error::WindowLimitReached)));
+
+    // A new window starts once the first one has elapsed
+    env.ledger().set_timestamp(10_000 + one_hour);
+    client.mint_inzpektor_id(&Address::generate(&env), &0, &vk_json, &mock_proof(&env, 4));
+    client.mint_inzpektor_id(&Address::generate(&env), &0, &vk_json, &mock_proof(&env, 5));
+    let result = client.try_mint_inzpektor_id(&Address::generate(&env), &0, &vk_json, &mock_proof(&env, 6));
+    assert_eq!(result, Err(Ok(Error::WindowLimitReached)));
+    assert_eq!(client.get_total_minted(), 4);
+
+    // Disabling the limit needs no window
+    client.set_mint_window_limit(&0, &0);
+    client.mint_inzpektor_id(&Address::generate(&env), &0, &vk_json, &mock_proof(&env, 6));
+    assert_eq!(client.get_total_minted(), 5);
+}
+
+#[test]
+fn test_empty_input_rejected_before_verification() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let nft_contract = env.register(mock_nft::MockNFT, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+    let verifier_client = mock_verifier::MockVerifierClient::new(&env, &verifier_contract);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+
+    let vk_json = Bytes::from_slice(&env, b"mock_vk");
+    let proof_blob = Bytes::from_slice(&env, b"mock_proof");
+    let empty = Bytes::new(&env);
+
+    let result = client.try_mint_inzpektor_id(&user, &0, &empty, &proof_blob);
+    assert_eq!(result, Err(Ok(Error::EmptyInput)));
+    let result = client.try_mint_inzpektor_id(&user, &0, &vk_json, &empty);
+    assert_eq!(result, Err(Ok(Error::EmptyInput)));
+    assert!(!verifier_client.was_called());
+
+    // Verifiers that keep their key on-chain do not need one
+    let stored_vk_verifier = env.register(mock_groth16_verifier::MockGroth16Verifier, ());
+    client.set_verifier_contract(&stored_vk_verifier);
+    client.set_verifier_abi(&Symbol::new(&env, "verify_with_stored_vk"), &VerifierArgs::ProofOnly);
+    let result = client.try_mint_inzpektor_id(&user, &0, &vk_json, &empty);
+    assert_eq!(result, Err(Ok(Error::EmptyInput)));
+    let token_id = client.mint_inzpektor_id(&user, &0, &empty, &proof_blob);
+    assert_eq!(client.get_nft_owner(&token_id), user);
+}
+
+#[test]
+fn test_mint_with_tier() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let nft_contract = env.register(mock_nft::MockNFT, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+    let verifier_client = mock_verifier::MockVerifierClient::new(&env, &verifier_contract);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+
+    let adult = Symbol::new(&env, "over_18");
+    let senior = Symbol::new(&env, "over_65");
+    client.set_tier_allowed(&adult, &true);
+    assert_emitted(&env, &contract_id, TierAllowedSet { admin: admin.clone(), tier: adult.clone(), allowed: true });
+    client.set_tier_allowed(&senior, &true);
+
+    let vk_json = Bytes::from_slice(&env, b"mock_vk");
+
+    // Unknown tiers are rejected before verification
+    let result = client.try_mint_with_tier(&user, &0, &vk_json, &mock_proof(&env, 0), &Symbol::new(&env, "vip"));
+    assert_eq!(result, Err(Ok(Error::TierNotAllowed)));
+    assert!(!verifier_client.was_called());
+
+    let adult_token = client.mint_with_tier(&user, &0, &vk_json, &mock_proof(&env, 1), &adult);
+    let senior_token = client.mint_with_tier(&user, &0, &vk_json, &mock_proof(&env, 2), &senior);
+    assert_eq!(client.get_token_tier(&adult_token), Some(adult));
+    assert_eq!(client.get_token_tier(&senior_token), Some(senior.clone()));
+
+    // Untiered mints have no tier, and removed tiers can no longer be minted
+    let plain_token = client.mint_inzpektor_id(&user, &0, &vk_json, &mock_proof(&env, 3));
+    assert_eq!(client.get_token_tier(&plain_token), None);
+    client.set_tier_allowed(&senior, &false);
+    assert!(!client.is_tier_allowed(&senior));
+    let result = client.try_mint_with_tier(&user, &0, &vk_json, &mock_proof(&env, 4), &senior);
+    assert_eq!(result, Err(Ok(Error::TierNotAllowed)));
+}
+
+#[test]
+fn test_subject_binding() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let other_user = Address::generate(&env);
+    let verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let nft_contract = env.register(mock_nft::MockNFT, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+    let verifier_client = mock_verifier::MockVerifierClient::new(&env, &verifier_contract);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+
+    // The subject is the second public input
+    client.set_subject_binding(&Some(1));
+    assert_emitted(&env, &contract_id, SubjectBindingSet { admin, input_index: Some(1) });
+    assert_eq!(client.get_subject_binding(), Some(1));
+
+    // [count = 2][unrelated input][subject hash][proof]
+    let unrelated = Bytes::from_array(&env, &[7u8; 32]);
+    let subject: Bytes = client.subject_hash(&user).into();
+    let proof_blob = packed_proof(&env, 2, &[unrelated.clone(), subject.clone()], 456);
+    let vk_json = Bytes::from_slice(&env, b"mock_vk");
+
+    // The proof cannot mint to anyone else
+    let result = client.try_mint_inzpektor_id(&other_user, &0, &vk_json, &proof_blob);
+    assert_eq!(result, Err(Ok(Error::SubjectMismatch)));
+    assert!(!verifier_client.was_called());
+
+    // Blobs too short to hold the subject are rejected too
+    let short_blob = Bytes::from_slice(&env, b"mock_proof");
+    let result = client.try_mint_inzpektor_id(&user, &0, &vk_json, &short_blob);
+    assert_eq!(result, Err(Ok(Error::SubjectMismatch)));
+
+    // The subject must lie within the public inputs the verifier reads, not
+    // at the start of the proof section, whatever the header claims
+    let in_proof = packed_proof(&env, 1, &[unrelated.clone(), subject.clone()], 455);
+    let result = client.try_mint_inzpektor_id(&user, &0, &vk_json, &in_proof);
+    assert_eq!(result, Err(Ok(Error::SubjectMismatch)));
+    let inflated = packed_proof(&env, 2, &[unrelated.clone(), subject.clone()], 455);
+    let result = client.try_mint_inzpektor_id(&user, &0, &vk_json, &inflated);
+    assert_eq!(result, Err(Ok(Error::SubjectMismatch)));
+
+    // Shorter 440-field proofs leave the same inputs in place
+    let short_proof = packed_proof(&env, 2, &[unrelated, subject], 440);
+    let token_id = client.mint_inzpektor_id(&user, &0, &vk_json, &short_proof);
+    assert_eq!(client.get_nft_owner(&token_id), user);
+
+    let token_id = client.mint_inzpektor_id(&user, &0, &vk_json, &proof_blob);
+    assert_eq!(client.get_nft_owner(&token_id), user);
+
+    // Without the binding any recipient is accepted
+    client.set_subject_binding(&None);
+    client.mint_inzpektor_id(&other_user, &0, &vk_json, &mock_proof(&env, 0));
+}
+
+#[test]
+fn test_verifier_result_checked() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let verifier_contract = env.register(mock_status_verifier::MockStatusVerifier, ());
+    let nft_contract = env.register(mock_nft::MockNFT, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+    let verifier_client = mock_status_verifier::MockStatusVerifierClient::new(&env, &verifier_contract);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+
+    let vk_json = Bytes::from_slice(&env, b"mock_vk");
+    let proof_blob = mock_proof(&env, 0);
+    let other_proof = mock_proof(&env, 1);
+
+    // Boolean status
+    client.set_verifier_abi(&Symbol::new(&env, "verify_bool"), &VerifierArgs::VkThenProof);
+    verifier_client.set_valid(&false);
+    let result = client.try_mint_inzpektor_id(&user, &0, &vk_json, &proof_blob);
+    assert_eq!(result, Err(Ok(Error::ProofInvalid)));
+
+    verifier_client.set_valid(&true);
+    let token_id = client.mint_inzpektor_id(&user, &0, &vk_json, &proof_blob);
+    let proof_hash: BytesN<32> = env.crypto().keccak256(&proof_blob).into();
+    assert_eq!(client.get_token_proof(&nft_contract, &token_id), Some(proof_hash));
+
+    // Proof id, with all zeroes as the failure sentinel
+    client.set_verifier_abi(&Symbol::new(&env, "verify_id"), &VerifierArgs::VkThenProof);
+    verifier_client.set_valid(&false);
+    let result = client.try_mint_inzpektor_id(&user, &0, &vk_json, &other_proof);
+    assert_eq!(result, Err(Ok(Error::ProofInvalid)));
+
+    verifier_client.set_valid(&true);
+    let token_id = client.mint_inzpektor_id(&user, &0, &vk_json, &other_proof);
+    assert_eq!(client.get_token_proof(&nft_contract, &token_id), Some(BytesN::from_array(&env, &[4u8; 32])));
+
+    // Anything else is not a success
+    client.set_verifier_abi(&Symbol::new(&env, "verify_unexpected"), &VerifierArgs::VkThenProof);
+    let result = client.try_mint_inzpektor_id(&user, &0, &vk_json, &mock_proof(&env, 2));
+    assert_eq!(result, Err(Ok(Error::ProofInvalid)));
+    assert_eq!(client.get_total_minted(), 2);
+}
+
+#[test]
+fn test_proof_system_routing() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let ultrahonk_verifier = env.register(mock_verifier::MockVerifier, ());
+    let groth16_verifier = env.register(mock_groth16_verifier::MockGroth16Verifier, ());
+    let nft_contract = env.register(mock_nft::MockNFT, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+
+    setup_contract_storage(&client, &admin, &ultrahonk_verifier, &nft_contract);
+
+    let ultrahonk = Symbol::new(&env, "ultrahonk");
+    let groth16 = Symbol::new(&env, "groth16");
+    client.set_proof_system(&ultrahonk, &ultrahonk_verifier, &Symbol::new(&env, "verify_proof"), &VerifierArgs::VkThenProof);
+    client.set_proof_system(&groth16, &groth16_verifier, &Symbol::new(&env, "verify"), &VerifierArgs::ProofThenVk);
+    let groth16_route = ProofSystemRoute {
+        verifier_contract: groth16_verifier.clone(),
+        abi: VerifierAbi { function: Symbol::new(&env, "verify"), args: VerifierArgs::ProofThenVk },
+    };
+    assert_emitted(&env, &contract_id, ProofSystemSet { admin: admin.clone(), proof_system: groth16.clone(), route: groth16_route.clone() });
+    assert_eq!(client.get_proof_system(&groth16), Some(groth16_route));
+
+    let vk_json = Bytes::from_slice(&env, b"mock_vk");
+
+    // Each system reaches its own verifier
+    let ultrahonk_token = client.mint_with_proof_system(&user, &0, &vk_json, &mock_proof(&env, 0), &ultrahonk);
+    let groth16_token = client.mint_with_proof_system(&user, &0, &vk_json, &mock_proof(&env, 1), &groth16);
+    assert_eq!(client.get_token_verifier(&nft_contract, &ultrahonk_token), Some(ultrahonk_verifier));
+    assert_eq!(client.get_token_proof(&nft_contract, &ultrahonk_token), Some(BytesN::from_array(&env, &[1u8; 32])));
+    assert_eq!(client.get_token_verifier(&nft_contract, &groth16_token), Some(groth16_verifier));
+    assert_eq!(client.get_token_proof(&nft_contract, &groth16_token), Some(BytesN::from_array(&env, &[2u8; 32])));
+
+    // Unknown or removed systems are rejected
+    let result = client.try_mint_with_proof_system(&user, &0, &vk_json, &mock_proof(&env, 2), &Symbol::new(&env, "plonk"));
+    assert_eq!(result, Err(Ok(Error::UnknownProofSystem)));
+    client.remove_proof_system(&groth16);
+    assert_emitted(&env, &contract_id, ProofSystemRemoved { admin, proof_system: groth16.clone() });
+    let result = client.try_mint_with_proof_system(&user, &0, &vk_json, &mock_proof(&env, 3), &groth16);
+    assert_eq!(result, Err(Ok(Error::UnknownProofSystem)));
+}
+
+#[test]
+fn test_mint_records_in_persistent_storage() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let user = Address::generate(&env);
+    let verifier_contract = env.register(mock_verifier::MockVerifier, ());
+    let nft_contract = env.register(mock_nft::MockNFT, ());
+
+    let contract_id = env.register(InzpektorHandlerContract, ());
+    let client = InzpektorHandlerContractClient::new(&env, &contract_id);
+
+    setup_contract_storage(&client, &admin, &verifier_contract, &nft_contract);
+
+    let vk_json = Bytes::from_slice(&env, b"mock_vk");
+    let proof_blob = Bytes::from_slice(&env, b"mock_proof");
+    let token_id = client.mint_inzpektor_id(&user, &0, &vk_json, &proof_blob);
+
+    // Per-token and per-user records stay out of the instance entry
+    env.as_contract(&contract_id, || {
+        let keys = [
+            DataKey::TokenProof(nft_contract.clone(), token_id),
+            DataKey::TokenVerifier(nft_contract.clone(), token_id),
+            DataKey::LastMint(user.clone()),
+        ];
+        for key in keys.iter() {
+            assert!(!env.storage().instance().has(key));
+            assert!(env.storage().persistent().has(key));
+            assert!(env.storage().persistent().get_ttl(key) >= RECORD_TTL_THRESHOLD);
+        }
+    });
+}
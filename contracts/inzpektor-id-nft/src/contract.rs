@@ -2,14 +2,99 @@
 // Compatible with OpenZeppelin Stellar Soroban Contracts ^0.4.1
 
 
-use soroban_sdk::{Address, Env, String, contract, contractimpl, contracttype};
+use soroban_sdk::{Address, Env, IntoVal, String, TryFromVal, Val, Vec, contract, contracterror, contractevent, contractimpl, contracttype, panic_with_error};
 use stellar_access::ownable::{self as ownable};
 use stellar_macros::{default_impl, only_owner};
-use stellar_tokens::non_fungible::{Base, NonFungibleToken, enumerable::{NonFungibleEnumerable, Enumerable}};
+use stellar_tokens::non_fungible::{Base, NonFungibleToken, burnable::emit_burn, emit_transfer, enumerable::{NonFungibleEnumerable, Enumerable}, sequential};
+
+const DAY_IN_LEDGERS: u32 = 17280;
+// Per-token data lives in persistent storage, so the instance entry (loaded on
+// every call) does not grow with each mint
+const TOKEN_TTL_THRESHOLD: u32 = 30 * DAY_IN_LEDGERS;
+const TOKEN_TTL_EXTEND_TO: u32 = 120 * DAY_IN_LEDGERS;
+
+/// Most tokens a single `expiry_histogram` call will read
+const MAX_HISTOGRAM_SCAN: u32 = 200;
 
 #[contracttype]
 pub enum DataKey {
     Expiration(u32), // token_id -> expiration_timestamp
+    IssuedAt(u32),   // token_id -> mint timestamp
+    ValidFrom(u32),  // token_id -> activation timestamp, if pre-issued
+    ExpiryPolicy,
+    ExpiryGranularity,
+    MaxExpiryHorizon,
+    TransferAllowed(Address), // escrow/upgrade contracts tokens may move to or from
+    Replaces(u32),   // token_id -> the token it was minted to replace
+    ReplacedBy(u32), // token_id -> the token that replaced it
+    BurnDisabled,
+    HolderCount,
+    Minter(Address),
+    Issuer(u32), // token_id -> owner or minter that minted it
+}
+
+#[contracterror]
+#[repr(u32)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Error {
+    AlreadyInitialized = 1,
+    IndexOutOfBounds = 2,
+    ExpiryTooFar = 3,
+    TransferNotAllowed = 4,
+    BurnDisabled = 5,
+    InvalidBuckets = 6,
+    ScanTooLarge = 7,
+    NotMinter = 8,
+    ExpiryPolicyLocked = 9,
+    InvalidValidFrom = 10,
+}
+
+/// Whether a token is still valid at the exact second of its expiration
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExpiryPolicy {
+    /// Expired only once `now > expires_at` (default)
+    Exclusive,
+    /// Expired as soon as `now >= expires_at`
+    Inclusive,
+}
+
+/// Emitted when a holder burns their own token through `surrender`
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Surrender {
+    #[topic]
+    pub token_id: u32,
+    pub holder: Address,
+}
+
+/// Emitted whenever a token's expiration is assigned, at mint or renewal
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExpirySet {
+    #[topic]
+    pub token_id: u32,
+    pub expires_at: u64,
+}
+
+/// Everything a client needs to render a credential, in one call
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenInfo {
+    pub token_id: u32,
+    pub owner: Address,
+    pub expires_at: u64,
+    pub expired: bool,
+}
+
+/// Emitted when the owner moves a token through `force_transfer`
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Recovery {
+    #[topic]
+    pub token_id: u32,
+    pub from: Address,
+    pub to: Address,
 }
 
 #[contract]
@@ -17,43 +102,577 @@ pub struct INZPEKTORID;
 
 #[contractimpl]
 impl INZPEKTORID {
-    pub fn initialize(e: &Env, owner: Address) {
+    /// Initialize with the default INZPEKTOR-ID branding
+    pub fn initialize(e: &Env, owner: Address) -> Result<(), Error> {
+        let name = String::from_str(e, "INZPEKTOR-ID");
+        let symbol = String::from_str(e, "IZK");
         let uri = String::from_str(e, "https://www.inzpektor.com/ids/");
-        let name = String::from_str(&e, "INZPEKTOR-ID");
-        let symbol = String::from_str(&e, "IZK");
-        Base::set_metadata(&e, uri, name, symbol);
+        Self::initialize_with_metadata(e, owner, name, symbol, uri)
+    }
+
+    /// Initialize with custom metadata, so the same bytecode can serve
+    /// differently-branded credential programs
+    pub fn initialize_with_metadata(e: &Env, owner: Address, name: String, symbol: String, base_uri: String) -> Result<(), Error> {
+        // The owner is only ever set here, so its presence marks initialization
+        if ownable::get_owner(e).is_some() {
+            return Err(Error::AlreadyInitialized);
+        }
+
+        Base::set_metadata(e, base_uri, name, symbol);
         ownable::set_owner(e, &owner);
+        Ok(())
+    }
+
+    /// Mint a token with an expiration timestamp (0 = never expires).
+    /// Emits the standard `["mint", to]` event with `token_id` as data.
+    #[only_owner]
+    pub fn mint(e: &Env, to: Address, expires_at: u64) -> Result<u32, Error> {
+        Self::mint_token(e, &Self::owner(e), &to, expires_at)
+    }
+
+    /// Mint as one of the minters approved with `set_minter`; otherwise the
+    /// same as `mint`. Requires the minter's authorization.
+    pub fn mint_by(e: &Env, minter: Address, to: Address, expires_at: u64) -> Result<u32, Error> {
+        minter.require_auth();
+        if !Self::is_minter(e.clone(), minter.clone()) {
+            return Err(Error::NotMinter);
+        }
+        Self::mint_token(e, &minter, &to, expires_at)
+    }
+
+    #[only_owner]
+    pub fn set_minter(e: &Env, minter: Address, allowed: bool) {
+        if allowed {
+            e.storage().instance().set(&DataKey::Minter(minter), &true);
+        } else {
+            e.storage().instance().remove(&DataKey::Minter(minter));
+        }
+    }
+
+    pub fn is_minter(e: Env, minter: Address) -> bool {
+        e.storage().instance().has(&DataKey::Minter(minter))
+    }
+
+    /// Mint a token valid for `validity_secs` from now (0 = never expires)
+    #[only_owner]
+    pub fn mint_for_duration(e: &Env, to: Address, validity_secs: u64) -> Result<u32, Error> {
+        let expires_at = match validity_secs {
+            0 => 0,
+            validity_secs => e.ledger().timestamp().saturating_add(validity_secs),
+        };
+        Self::mint_token(e, &Self::owner(e), &to, expires_at)
+    }
+
+    /// Pre-issue a token that only becomes active at `valid_from`, which must
+    /// come before `expires_at` unless the token never expires
+    #[only_owner]
+    pub fn mint_with_valid_from(e: &Env, to: Address, valid_from: u64, expires_at: u64) -> Result<u32, Error> {
+        if expires_at != 0 && valid_from >= expires_at {
+            return Err(Error::InvalidValidFrom);
+        }
+        let token_id = Self::mint_token(e, &Self::owner(e), &to, expires_at)?;
+        Self::set_token_data(e, &DataKey::ValidFrom(token_id), &valid_from);
+        Ok(token_id)
+    }
+
+    /// Mint a renewal that links back to `replaces_token_id`, optionally
+    /// burning the old token. The links survive the burn for auditing.
+    #[only_owner]
+    pub fn mint_replacement(e: &Env, to: Address, expires_at: u64, replaces_token_id: u32, burn_old: bool) -> Result<u32, Error> {
+        if burn_old {
+            Self::require_burnable(e)?;
+        }
+
+        // Panics if the old token does not exist
+        let old_holder = Base::owner_of(e, replaces_token_id);
+
+        let token_id = Self::mint_token(e, &Self::owner(e), &to, expires_at)?;
+        Self::set_token_data(e, &DataKey::Replaces(token_id), &replaces_token_id);
+        Self::set_token_data(e, &DataKey::ReplacedBy(replaces_token_id), &token_id);
+
+        if burn_old {
+            Self::owner_burn(e, &old_holder, replaces_token_id);
+        }
+
+        Ok(token_id)
     }
 
+    fn owner(e: &Env) -> Address {
+        ownable::get_owner(e).expect("owner not set")
+    }
+
+    fn mint_token(e: &Env, issuer: &Address, to: &Address, expires_at: u64) -> Result<u32, Error> {
+        // Round before checking, so rounding cannot push past the horizon
+        let expires_at = Self::round_expiration(e, expires_at);
+        Self::check_expiry_horizon(e, expires_at)?;
+
+        // Mint the token; ids come from the sequential counter, which keeps
+        // advancing after burns (unlike total_supply)
+        let token_id = Enumerable::sequential_mint(e, to);
+        Self::update_holder_count(e, None, Some(to));
+
+        // Store the expiration and issuance timestamps for this specific token
+        Self::set_expiration(e, token_id, expires_at);
+        Self::set_token_data(e, &DataKey::IssuedAt(token_id), &e.ledger().timestamp());
+        Self::set_token_data(e, &DataKey::Issuer(token_id), issuer);
+
+        Ok(token_id)
+    }
+
+    /// With a horizon configured, the rounded `expires_at` must be no later
+    /// than `now + horizon`. No-expiration (0) counts as infinitely far out
+    /// and is rejected too, as is `u64::MAX`.
+    fn check_expiry_horizon(e: &Env, expires_at: u64) -> Result<(), Error> {
+        let horizon = Self::get_max_expiry_horizon(e.clone());
+        if horizon == 0 {
+            return Ok(());
+        }
+        if expires_at == 0 || expires_at > e.ledger().timestamp().saturating_add(horizon) {
+            return Err(Error::ExpiryTooFar);
+        }
+        Ok(())
+    }
+
+    /// Extend the expiration of many tokens at once, e.g. for a program-wide
+    /// extension. Tokens without an expiration (including burned ones) are
+    /// skipped, and new expirations are rounded like minted ones. Fails with
+    /// `ExpiryTooFar` if any renewal would exceed the max expiry horizon.
+    /// Returns how many tokens were updated.
+    #[only_owner]
+    pub fn bulk_renew(e: &Env, token_ids: Vec<u32>, extra_secs: u64) -> Result<u32, Error> {
+        let mut updated = 0;
+        for token_id in token_ids.iter() {
+            let expiration = Self::get_expiration(e.clone(), token_id);
+            if expiration == 0 {
+                continue;
+            }
+            let new_expiration = Self::round_expiration(e, expiration.saturating_add(extra_secs));
+            Self::check_expiry_horizon(e, new_expiration)?;
+            Self::set_expiration(e, token_id, new_expiration);
+            updated += 1;
+        }
+        Ok(updated)
+    }
+
+    /// Break-glass recovery: move a token from a compromised or lost account
+    /// without the holder's authorization. The expiration stays with the token.
+    #[only_owner]
+    pub fn force_transfer(e: &Env, token_id: u32, to: Address) {
+        Self::recover_token(e, token_id, to);
+    }
+
+    /// Recovery transfer that also resets the token's expiration, e.g. to give
+    /// the recovered credential a fresh validity period
+    #[only_owner]
+    pub fn transfer_and_renew(e: &Env, token_id: u32, to: Address, new_expires_at: u64) -> Result<(), Error> {
+        let new_expires_at = Self::round_expiration(e, new_expires_at);
+        Self::check_expiry_horizon(e, new_expires_at)?;
+
+        Self::recover_token(e, token_id, to);
+        Self::set_expiration(e, token_id, new_expires_at);
+        Ok(())
+    }
+
+    fn recover_token(e: &Env, token_id: u32, to: Address) {
+        let from = Base::owner_of(e, token_id);
+
+        Base::update(e, Some(&from), Some(&to), token_id);
+        if from != to {
+            Enumerable::remove_from_owner_enumeration(e, &from, token_id);
+            Enumerable::add_to_owner_enumeration(e, &to, token_id);
+        }
+        Self::update_holder_count(e, Some(&from), Some(&to));
+
+        emit_transfer(e, &from, &to, token_id);
+        Recovery { token_id, from, to }.publish(e);
+    }
+
+    /// Allow or disallow holder transfers to and from `account`, e.g. an
+    /// escrow or upgrade contract
+    #[only_owner]
+    pub fn set_transfer_allowed(e: &Env, account: Address, allowed: bool) {
+        if allowed {
+            e.storage().instance().set(&DataKey::TransferAllowed(account), &true);
+        } else {
+            e.storage().instance().remove(&DataKey::TransferAllowed(account));
+        }
+    }
+
+    pub fn is_transfer_allowed(e: Env, account: Address) -> bool {
+        e.storage().instance().has(&DataKey::TransferAllowed(account))
+    }
+
+    /// Tokens are soulbound: a holder transfer must have an allowlisted
+    /// counterparty. The owner's `force_transfer` is not subject to this.
+    fn require_transfer_allowed(e: &Env, from: &Address, to: &Address) {
+        if !Self::is_transfer_allowed(e.clone(), from.clone()) && !Self::is_transfer_allowed(e.clone(), to.clone()) {
+            panic_with_error!(e, Error::TransferNotAllowed);
+        }
+    }
+
+    /// Let the holder revoke their own credential: burns the token and
+    /// clears its expiration. Requires the holder's authorization.
+    pub fn surrender(e: &Env, token_id: u32) -> Result<(), Error> {
+        Self::require_burnable(e)?;
+        let holder = Base::owner_of(e, token_id);
+
+        // Base::burn requires `holder` auth
+        Enumerable::burn(e, &holder, token_id);
+        Self::update_holder_count(e, Some(&holder), None);
+        Self::remove_token_data(e, token_id);
+
+        Surrender { token_id, holder }.publish(e);
+        Ok(())
+    }
+
+    /// Burn a token on the holder's behalf. `operator` must be approved for
+    /// the token (or for all of the holder's tokens), or be the contract owner.
+    pub fn burn_from(e: &Env, operator: Address, token_id: u32) -> Result<(), Error> {
+        Self::require_burnable(e)?;
+        let holder = Base::owner_of(e, token_id);
+
+        if ownable::get_owner(e) == Some(operator.clone()) {
+            operator.require_auth();
+            Self::owner_burn(e, &holder, token_id);
+        } else {
+            // Base::burn_from requires `operator` auth and checks its approval
+            Enumerable::burn_from(e, &operator, &holder, token_id);
+            Self::update_holder_count(e, Some(&holder), None);
+            Self::remove_token_data(e, token_id);
+        }
+        Ok(())
+    }
+
+    /// Permanently forbid burning, for programs that must retain every
+    /// credential on record. Burning is allowed by default, and once disabled
+    /// it cannot be re-enabled.
     #[only_owner]
-    pub fn mint(e: &Env, to: Address, expires_at: u64) -> u32 {
-        // Get current supply to know what token_id will be minted
-        let token_id = Enumerable::total_supply(e);
+    pub fn disable_burning(e: &Env) {
+        e.storage().instance().set(&DataKey::BurnDisabled, &true);
+    }
+
+    pub fn is_burnable(e: Env) -> bool {
+        !e.storage().instance().has(&DataKey::BurnDisabled)
+    }
+
+    fn require_burnable(e: &Env) -> Result<(), Error> {
+        if !Self::is_burnable(e.clone()) {
+            return Err(Error::BurnDisabled);
+        }
+        Ok(())
+    }
+
+    /// Burn without the holder's authorization; callers must require the owner's
+    fn owner_burn(e: &Env, holder: &Address, token_id: u32) {
+        Base::update(e, Some(holder), None, token_id);
+        Enumerable::remove_from_enumerations(e, holder, token_id);
+        Self::update_holder_count(e, Some(holder), None);
+        emit_burn(e, holder, token_id);
+        Self::remove_token_data(e, token_id);
+    }
+
+    /// Round `expires_at` up to the next multiple of the configured
+    /// granularity. No-expiration (0) and values that would overflow are
+    /// left as-is.
+    fn round_expiration(e: &Env, expires_at: u64) -> u64 {
+        let granularity = Self::get_expiry_granularity(e.clone());
+        if granularity == 0 || expires_at == 0 {
+            return expires_at;
+        }
+        match expires_at % granularity {
+            0 => expires_at,
+            remainder => expires_at.checked_add(granularity - remainder).unwrap_or(expires_at),
+        }
+    }
+
+    /// Adjust the distinct-holder tally after a token moved from `from` to
+    /// `to` (None for mints and burns), using the balances after the move
+    fn update_holder_count(e: &Env, from: Option<&Address>, to: Option<&Address>) {
+        if from == to {
+            return;
+        }
+        let mut holder_count = Self::get_holder_count(e.clone());
+        if let Some(to) = to {
+            if Base::balance(e, to) == 1 {
+                holder_count += 1;
+            }
+        }
+        if let Some(from) = from {
+            if Base::balance(e, from) == 0 {
+                holder_count = holder_count.saturating_sub(1);
+            }
+        }
+        e.storage().instance().set(&DataKey::HolderCount, &holder_count);
+    }
+
+    fn set_token_data<V: IntoVal<Env, Val>>(e: &Env, key: &DataKey, value: &V) {
+        e.storage().persistent().set(key, value);
+        e.storage().persistent().extend_ttl(key, TOKEN_TTL_THRESHOLD, TOKEN_TTL_EXTEND_TO);
+    }
+
+    /// Read per-token data, keeping it alive while it is still in use
+    fn get_token_data<V: TryFromVal<Env, Val>>(e: &Env, key: &DataKey) -> Option<V> {
+        let value = e.storage().persistent().get(key);
+        if value.is_some() {
+            e.storage().persistent().extend_ttl(key, TOKEN_TTL_THRESHOLD, TOKEN_TTL_EXTEND_TO);
+        }
+        value
+    }
 
-        // Mint the token
-        Enumerable::sequential_mint(e, &to);
+    fn set_expiration(e: &Env, token_id: u32, expires_at: u64) {
+        Self::set_token_data(e, &DataKey::Expiration(token_id), &expires_at);
+        ExpirySet { token_id, expires_at }.publish(e);
+    }
 
-        // Store the expiration timestamp for this specific token
-        e.storage().instance().set(&DataKey::Expiration(token_id), &expires_at);
+    /// Clear a burned token's per-token data. The `Replaces` / `ReplacedBy`
+    /// links are kept so a renewal chain stays traceable after `burn_old`.
+    fn remove_token_data(e: &Env, token_id: u32) {
+        e.storage().persistent().remove(&DataKey::Expiration(token_id));
+        e.storage().persistent().remove(&DataKey::IssuedAt(token_id));
+        e.storage().persistent().remove(&DataKey::ValidFrom(token_id));
+        e.storage().persistent().remove(&DataKey::Issuer(token_id));
+    }
 
-        token_id
+    /// Id the next mint will receive. Unlike `total_supply`, this keeps
+    /// advancing after burns.
+    pub fn next_token_id(e: Env) -> u32 {
+        sequential::next_token_id(&e)
     }
 
     /// Get the expiration timestamp for a specific token
     pub fn get_expiration(e: Env, token_id: u32) -> u64 {
+        Self::get_token_data(&e, &DataKey::Expiration(token_id))
+            .unwrap_or(0)
+    }
+
+    /// Get the timestamp a token was minted at
+    pub fn get_issued_at(e: Env, token_id: u32) -> u64 {
+        Self::get_token_data(&e, &DataKey::IssuedAt(token_id))
+            .unwrap_or(0)
+    }
+
+    /// Owner or minter that minted a token
+    pub fn get_issuer(e: Env, token_id: u32) -> Option<Address> {
+        Self::get_token_data(&e, &DataKey::Issuer(token_id))
+    }
+
+    /// Get the timestamp a token becomes active; defaults to its issuance
+    pub fn get_valid_from(e: Env, token_id: u32) -> u64 {
+        Self::get_token_data(&e, &DataKey::ValidFrom(token_id))
+            .unwrap_or_else(|| Self::get_issued_at(e, token_id))
+    }
+
+    /// Number of distinct addresses holding at least one token
+    pub fn get_holder_count(e: Env) -> u32 {
         e.storage()
             .instance()
-            .get(&DataKey::Expiration(token_id))
+            .get(&DataKey::HolderCount)
             .unwrap_or(0)
     }
 
+    /// Token that `token_id` was minted to replace, if any
+    pub fn get_replaces(e: Env, token_id: u32) -> Option<u32> {
+        Self::get_token_data(&e, &DataKey::Replaces(token_id))
+    }
+
+    /// Token minted to replace `token_id`, if any
+    pub fn get_replaced_by(e: Env, token_id: u32) -> Option<u32> {
+        Self::get_token_data(&e, &DataKey::ReplacedBy(token_id))
+    }
+
+    /// Check if a token has reached its `valid_from` and is not expired
+    pub fn is_active(e: Env, token_id: u32) -> bool {
+        e.ledger().timestamp() >= Self::get_valid_from(e.clone(), token_id)
+            && !Self::is_expired(e, token_id)
+    }
+
+    /// Expiration of the `index`-th token held by `owner`
+    pub fn expiration_of_owner_by_index(e: Env, owner: Address, index: u32) -> Result<u64, Error> {
+        if index >= Base::balance(&e, &owner) {
+            return Err(Error::IndexOutOfBounds);
+        }
+        let token_id = Enumerable::get_owner_token_id(&e, &owner, index);
+        Ok(Self::get_expiration(e, token_id))
+    }
+
     /// Check if a token is expired
     pub fn is_expired(e: Env, token_id: u32) -> bool {
         let expiration = Self::get_expiration(e.clone(), token_id);
         if expiration == 0 {
             return false; // No expiration set
         }
-        e.ledger().timestamp() > expiration
+        match Self::get_expiry_policy(e.clone()) {
+            ExpiryPolicy::Exclusive => e.ledger().timestamp() > expiration,
+            ExpiryPolicy::Inclusive => e.ledger().timestamp() >= expiration,
+        }
+    }
+
+    /// Seconds until the last second a token is valid under the expiry
+    /// policy, so 0 means it expires after the current second. Negative
+    /// exactly when `is_expired` is true. Tokens without an expiration
+    /// return `i64::MAX`.
+    pub fn time_to_expiry(e: Env, token_id: u32) -> i64 {
+        let expiration = Self::get_expiration(e.clone(), token_id);
+        if expiration == 0 {
+            return i64::MAX; // No expiration set
+        }
+        // Under Inclusive the expiration second itself is already expired
+        let last_valid = match Self::get_expiry_policy(e.clone()) {
+            ExpiryPolicy::Exclusive => expiration as i128,
+            ExpiryPolicy::Inclusive => expiration as i128 - 1,
+        };
+        let remaining = last_valid - e.ledger().timestamp() as i128;
+        remaining.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+    }
+
+    /// Align minted expirations to multiples of `granularity_secs` (e.g.
+    /// 86400 for day boundaries), rounding up. 0 disables rounding.
+    #[only_owner]
+    pub fn set_expiry_granularity(e: &Env, granularity_secs: u64) {
+        e.storage().instance().set(&DataKey::ExpiryGranularity, &granularity_secs);
+    }
+
+    pub fn get_expiry_granularity(e: Env) -> u64 {
+        e.storage()
+            .instance()
+            .get(&DataKey::ExpiryGranularity)
+            .unwrap_or(0)
+    }
+
+    /// Cap how far in the future minted tokens may expire. 0 removes the cap;
+    /// while set, tokens without an expiration can no longer be minted.
+    #[only_owner]
+    pub fn set_max_expiry_horizon(e: &Env, horizon_secs: u64) {
+        e.storage().instance().set(&DataKey::MaxExpiryHorizon, &horizon_secs);
+    }
+
+    pub fn get_max_expiry_horizon(e: Env) -> u64 {
+        e.storage()
+            .instance()
+            .get(&DataKey::MaxExpiryHorizon)
+            .unwrap_or(0)
+    }
+
+    /// Choose the expiry policy. It can only be set once, so the validity of
+    /// credentials already issued cannot be changed afterwards.
+    #[only_owner]
+    pub fn set_expiry_policy(e: &Env, policy: ExpiryPolicy) -> Result<(), Error> {
+        if e.storage().instance().has(&DataKey::ExpiryPolicy) {
+            return Err(Error::ExpiryPolicyLocked);
+        }
+        e.storage().instance().set(&DataKey::ExpiryPolicy, &policy);
+        Ok(())
+    }
+
+    pub fn get_expiry_policy(e: Env) -> ExpiryPolicy {
+        e.storage()
+            .instance()
+            .get(&DataKey::ExpiryPolicy)
+            .unwrap_or(ExpiryPolicy::Exclusive)
+    }
+
+    /// Number of active tokens, i.e. past `valid_from` and not expired, as
+    /// `is_active` reports them. Walks every token, so large collections
+    /// should use `active_supply_paginated` instead.
+    pub fn active_supply(e: Env) -> u32 {
+        let total_supply = Enumerable::total_supply(&e);
+        Self::active_supply_paginated(e, 0, total_supply)
+    }
+
+    /// Number of active tokens among global indices `start..start + limit`
+    pub fn active_supply_paginated(e: Env, start: u32, limit: u32) -> u32 {
+        let end = start.saturating_add(limit).min(Enumerable::total_supply(&e));
+        let mut active = 0;
+        for index in start..end {
+            let token_id = Enumerable::get_token_id(&e, index);
+            if Self::is_active(e.clone(), token_id) {
+                active += 1;
+            }
+        }
+        active
+    }
+
+    /// `(active, expired, pending)` token counts, where pending tokens were
+    /// pre-issued and have not reached `valid_from`. Reads every token, so
+    /// beyond a few hundred tokens use `supply_breakdown_paginated` and sum
+    /// the pages.
+    pub fn supply_breakdown(e: Env) -> (u32, u32, u32) {
+        let total_supply = Enumerable::total_supply(&e);
+        Self::supply_breakdown_paginated(e, 0, total_supply)
+    }
+
+    /// `supply_breakdown` over global indices `start..start + limit`
+    pub fn supply_breakdown_paginated(e: Env, start: u32, limit: u32) -> (u32, u32, u32) {
+        let end = start.saturating_add(limit).min(Enumerable::total_supply(&e));
+        let (mut active, mut expired, mut pending) = (0, 0, 0);
+        for index in start..end {
+            let token_id = Enumerable::get_token_id(&e, index);
+            if Self::is_expired(e.clone(), token_id) {
+                expired += 1;
+            } else if e.ledger().timestamp() < Self::get_valid_from(e.clone(), token_id) {
+                pending += 1;
+            } else {
+                active += 1;
+            }
+        }
+        (active, expired, pending)
+    }
+
+    /// Count unexpired tokens by time left: bucket `i` holds tokens expiring
+    /// more than `bucket_edges[i - 1]` and at most `bucket_edges[i]` seconds
+    /// from now. Edges must be strictly increasing; tokens without an
+    /// expiration or beyond the last edge are not counted. Collections larger
+    /// than `MAX_HISTOGRAM_SCAN` must use `expiry_histogram_paginated`.
+    pub fn expiry_histogram(e: Env, bucket_edges: Vec<u64>) -> Result<Vec<u32>, Error> {
+        let total_supply = Enumerable::total_supply(&e);
+        if total_supply > MAX_HISTOGRAM_SCAN {
+            return Err(Error::ScanTooLarge);
+        }
+        Self::expiry_histogram_paginated(e, bucket_edges, 0, total_supply)
+    }
+
+    /// `expiry_histogram` over global indices `start..start + limit`, reading
+    /// at most `MAX_HISTOGRAM_SCAN` tokens per call
+    pub fn expiry_histogram_paginated(e: Env, bucket_edges: Vec<u64>, start: u32, limit: u32) -> Result<Vec<u32>, Error> {
+        if limit > MAX_HISTOGRAM_SCAN {
+            return Err(Error::ScanTooLarge);
+        }
+        for i in 1..bucket_edges.len() {
+            if bucket_edges.get_unchecked(i) <= bucket_edges.get_unchecked(i - 1) {
+                return Err(Error::InvalidBuckets);
+            }
+        }
+
+        let mut counts = Vec::new(&e);
+        for _ in 0..bucket_edges.len() {
+            counts.push_back(0u32);
+        }
+
+        let now = e.ledger().timestamp();
+        let end = start.saturating_add(limit).min(Enumerable::total_supply(&e));
+        for index in start..end {
+            let token_id = Enumerable::get_token_id(&e, index);
+            let expiration = Self::get_expiration(e.clone(), token_id);
+            if expiration == 0 || Self::is_expired(e.clone(), token_id) {
+                continue;
+            }
+            let remaining = expiration.saturating_sub(now);
+            if let Some(bucket) = bucket_edges.iter().position(|edge| remaining <= edge) {
+                let bucket = bucket as u32;
+                counts.set(bucket, counts.get_unchecked(bucket) + 1);
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Owner, expiration and expiry status of a token
+    pub fn get_token_info(e: Env, token_id: u32) -> TokenInfo {
+        TokenInfo {
+            token_id,
+            owner: Base::owner_of(&e, token_id),
+            expires_at: Self::get_expiration(e.clone(), token_id),
+            expired: Self::is_expired(e, token_id),
+        }
     }
 }
 
@@ -62,6 +681,17 @@ impl INZPEKTORID {
 impl NonFungibleToken for INZPEKTORID {
     type ContractType = Enumerable;
 
+    fn transfer(e: &Env, from: Address, to: Address, token_id: u32) {
+        Self::require_transfer_allowed(e, &from, &to);
+        Enumerable::transfer(e, &from, &to, token_id);
+        Self::update_holder_count(e, Some(&from), Some(&to));
+    }
+
+    fn transfer_from(e: &Env, spender: Address, from: Address, to: Address, token_id: u32) {
+        Self::require_transfer_allowed(e, &from, &to);
+        Enumerable::transfer_from(e, &spender, &from, &to, token_id);
+        Self::update_holder_count(e, Some(&from), Some(&to));
+    }
 }
 
 //
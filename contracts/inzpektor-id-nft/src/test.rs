@@ -2,9 +2,16 @@
 
 extern crate std;
 
-use soroban_sdk::{ testutils::Address as _, Address, Env, String };
+use soroban_sdk::{ testutils::{storage::Persistent as _, Address as _, Events, Ledger, MockAuth, MockAuthInvoke}, Address, Env, Event, IntoVal, String, vec };
 
-use crate::contract::{ INZPEKTORID, INZPEKTORIDClient };
+use stellar_tokens::non_fungible::{ burnable::Burn, Mint };
+
+use crate::contract::{ DataKey, Error, ExpiryPolicy, ExpirySet, INZPEKTORID, INZPEKTORIDClient, Recovery, Surrender, TokenInfo };
+
+fn assert_emitted(env: &Env, contract_addr: &Address, event: impl Event) {
+    let expected = (contract_addr.clone(), event.topics(env), event.data(env));
+    assert!(env.events().all().contains(expected));
+}
 
 #[test]
 fn initial_state() {
@@ -227,6 +234,33 @@ fn test_metadata() {
     assert_eq!(client.symbol(), String::from_str(&env, "IZK"));
 }
 
+#[test]
+fn test_custom_metadata() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.initialize_with_metadata(
+        &owner,
+        &String::from_str(&env, "AUDITOR-ID"),
+        &String::from_str(&env, "AUD"),
+        &String::from_str(&env, "https://auditors.example.com/ids/"),
+    );
+
+    // Verify custom metadata
+    assert_eq!(client.name(), String::from_str(&env, "AUDITOR-ID"));
+    assert_eq!(client.symbol(), String::from_str(&env, "AUD"));
+
+    // Token URI uses the custom base URI
+    let token_id = client.mint(&user, &0);
+    assert_eq!(client.token_uri(&token_id), String::from_str(&env, "https://auditors.example.com/ids/0"));
+}
+
 #[test]
 fn test_zero_tokens_initially() {
     let env = Env::default();
@@ -297,3 +331,1044 @@ fn test_token_uri() {
     let token_uri = client.token_uri(&token_id);
     assert_eq!(token_uri, String::from_str(&env, "https://www.inzpektor.com/ids/0"));
 }
+
+#[test]
+fn test_force_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let lost_account = Address::generate(&env);
+    let recovery_account = Address::generate(&env);
+
+    client.initialize(&owner);
+
+    let expires_at = env.ledger().timestamp() + 365 * 24 * 60 * 60;
+    let token_id = client.mint(&lost_account, &expires_at);
+
+    client.force_transfer(&token_id, &recovery_account);
+
+    assert_emitted(&env, &contract_addr, Recovery {
+        token_id,
+        from: lost_account.clone(),
+        to: recovery_account.clone(),
+    });
+
+    // Ownership and enumeration moved to the recovery account
+    assert_eq!(client.owner_of(&token_id), recovery_account);
+    assert_eq!(client.balance(&lost_account), 0);
+    assert_eq!(client.balance(&recovery_account), 1);
+    assert_eq!(client.get_owner_token_id(&recovery_account, &0), token_id);
+
+    // Expiration travels with the token
+    assert_eq!(client.get_expiration(&token_id), expires_at);
+}
+
+#[test]
+fn test_get_token_info() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.initialize(&owner);
+
+    let expires_at = env.ledger().timestamp() + 365 * 24 * 60 * 60;
+    let token_id = client.mint(&user, &expires_at);
+
+    assert_eq!(
+        client.get_token_info(&token_id),
+        TokenInfo {
+            token_id,
+            owner: user,
+            expires_at,
+            expired: false,
+        }
+    );
+}
+
+#[test]
+fn test_surrender() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.initialize(&owner);
+
+    let expires_at = env.ledger().timestamp() + 365 * 24 * 60 * 60;
+    let token_id = client.mint(&user, &expires_at);
+
+    client.surrender(&token_id);
+    assert_emitted(&env, &contract_addr, Surrender { token_id, holder: user.clone() });
+
+    // Token is gone and its expiration cleared
+    assert_eq!(client.balance(&user), 0);
+    assert_eq!(client.total_supply(), 0);
+    assert!(client.try_owner_of(&token_id).is_err());
+    assert_eq!(client.get_expiration(&token_id), 0);
+
+    // Later mints keep using fresh ids and store expiration under them
+    let next_token_id = client.mint(&user, &(expires_at + 1));
+    assert_eq!(next_token_id, 1);
+    assert_eq!(client.get_expiration(&next_token_id), expires_at + 1);
+}
+
+#[test]
+fn test_surrender_requires_holder_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let user = Address::generate(&env);
+    let attacker = Address::generate(&env);
+
+    client.initialize(&owner);
+
+    let token_id = client.mint(&user, &0);
+
+    // Only the attacker authorizes the call
+    let result = client
+        .mock_auths(&[MockAuth {
+            address: &attacker,
+            invoke: &MockAuthInvoke {
+                contract: &contract_addr,
+                fn_name: "surrender",
+                args: (token_id,).into_val(&env),
+                sub_invokes: &[],
+            },
+        }])
+        .try_surrender(&token_id);
+    assert!(result.is_err());
+
+    // Token is untouched
+    assert_eq!(client.owner_of(&token_id), user);
+}
+
+#[test]
+fn test_expiry_policy_boundary() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let owner = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    let exclusive_addr = env.register(INZPEKTORID, ());
+    let exclusive = INZPEKTORIDClient::new(&env, &exclusive_addr);
+    exclusive.initialize(&owner);
+
+    let inclusive_addr = env.register(INZPEKTORID, ());
+    let inclusive = INZPEKTORIDClient::new(&env, &inclusive_addr);
+    inclusive.initialize(&owner);
+    inclusive.set_expiry_policy(&ExpiryPolicy::Inclusive);
+
+    let expires_at: u64 = 1_000;
+    let exclusive_token = exclusive.mint(&user, &expires_at);
+    let inclusive_token = inclusive.mint(&user, &expires_at);
+
+    // Exactly at the expiration timestamp
+    env.ledger().set_timestamp(expires_at);
+
+    // Default policy keeps the token valid at the boundary
+    assert_eq!(exclusive.get_expiry_policy(), ExpiryPolicy::Exclusive);
+    assert!(!exclusive.is_expired(&exclusive_token));
+
+    // Inclusive policy treats the boundary as expired
+    assert_eq!(inclusive.get_expiry_policy(), ExpiryPolicy::Inclusive);
+    assert!(inclusive.is_expired(&inclusive_token));
+
+    // time_to_expiry turns negative exactly when is_expired does
+    assert_eq!(exclusive.time_to_expiry(&exclusive_token), 0);
+    assert_eq!(inclusive.time_to_expiry(&inclusive_token), -1);
+
+    // One second before expiry is valid under both policies
+    env.ledger().set_timestamp(expires_at - 1);
+    assert!(!exclusive.is_expired(&exclusive_token));
+    assert!(!inclusive.is_expired(&inclusive_token));
+    assert_eq!(exclusive.time_to_expiry(&exclusive_token), 1);
+    assert_eq!(inclusive.time_to_expiry(&inclusive_token), 0);
+
+    // One second after is expired under both
+    env.ledger().set_timestamp(expires_at + 1);
+    assert!(exclusive.is_expired(&exclusive_token));
+    assert!(inclusive.is_expired(&inclusive_token));
+    assert_eq!(exclusive.time_to_expiry(&exclusive_token), -1);
+    assert_eq!(inclusive.time_to_expiry(&inclusive_token), -2);
+
+    // The policy cannot be changed once set
+    assert_eq!(inclusive.try_set_expiry_policy(&ExpiryPolicy::Exclusive), Err(Ok(Error::ExpiryPolicyLocked)));
+    assert_eq!(inclusive.get_expiry_policy(), ExpiryPolicy::Inclusive);
+}
+
+#[test]
+fn test_burn_from() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let user = Address::generate(&env);
+    let operator = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    client.initialize(&owner);
+
+    let expires_at = env.ledger().timestamp() + 365 * 24 * 60 * 60;
+    let approved_token = client.mint(&user, &expires_at);
+    let owner_token = client.mint(&user, &expires_at);
+
+    // An operator without approval is rejected
+    assert!(client.try_burn_from(&stranger, &approved_token).is_err());
+    assert_eq!(client.owner_of(&approved_token), user);
+
+    // An approved operator can burn
+    let live_until_ledger = env.ledger().sequence() + 100;
+    client.approve(&user, &operator, &approved_token, &live_until_ledger);
+    client.burn_from(&operator, &approved_token);
+    assert!(client.try_owner_of(&approved_token).is_err());
+    assert_eq!(client.get_expiration(&approved_token), 0);
+
+    // The contract owner can burn without approval
+    client.burn_from(&owner, &owner_token);
+    assert!(client.try_owner_of(&owner_token).is_err());
+    assert_eq!(client.balance(&user), 0);
+    assert_eq!(client.total_supply(), 0);
+}
+
+#[test]
+fn test_time_to_expiry() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.initialize(&owner);
+
+    env.ledger().set_timestamp(10_000);
+
+    let future_token = client.mint(&user, &15_000);
+    let past_token = client.mint(&user, &4_000);
+    let no_expiry_token = client.mint(&user, &0);
+    let far_token = client.mint(&user, &u64::MAX);
+
+    assert_eq!(client.time_to_expiry(&future_token), 5_000);
+    assert_eq!(client.time_to_expiry(&past_token), -6_000);
+    assert_eq!(client.time_to_expiry(&no_expiry_token), i64::MAX);
+
+    // Values beyond the i64 range are clamped
+    assert_eq!(client.time_to_expiry(&far_token), i64::MAX);
+}
+
+#[test]
+fn test_double_initialize_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let attacker = Address::generate(&env);
+
+    client.initialize(&owner);
+
+    // Neither entry point can re-initialize
+    assert_eq!(client.try_initialize(&attacker), Err(Ok(Error::AlreadyInitialized)));
+    assert_eq!(
+        client.try_initialize_with_metadata(
+            &attacker,
+            &String::from_str(&env, "FAKE-ID"),
+            &String::from_str(&env, "FAKE"),
+            &String::from_str(&env, "https://fake.example.com/"),
+        ),
+        Err(Ok(Error::AlreadyInitialized))
+    );
+
+    // Original metadata persists
+    assert_eq!(client.name(), String::from_str(&env, "INZPEKTOR-ID"));
+    assert_eq!(client.symbol(), String::from_str(&env, "IZK"));
+
+    // Original owner still controls minting
+    let user = Address::generate(&env);
+    let result = client
+        .mock_auths(&[MockAuth {
+            address: &attacker,
+            invoke: &MockAuthInvoke {
+                contract: &contract_addr,
+                fn_name: "mint",
+                args: (user.clone(), 0u64).into_val(&env),
+                sub_invokes: &[],
+            },
+        }])
+        .try_mint(&user, &0);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_standard_mint_and_burn_events() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.initialize(&owner);
+
+    // Mint publishes the OpenZeppelin standard ["mint", to] event
+    let token_id = client.mint(&user, &0);
+    assert_emitted(&env, &contract_addr, Mint { to: user.clone(), token_id });
+
+    // Burning publishes the standard ["burn", from] event
+    client.surrender(&token_id);
+    assert_emitted(&env, &contract_addr, Burn { from: user, token_id });
+}
+
+#[test]
+fn test_expiration_of_owner_by_index() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let user = Address::generate(&env);
+    let other = Address::generate(&env);
+
+    client.initialize(&owner);
+
+    let current_time = env.ledger().timestamp();
+    let one_year: u64 = 365 * 24 * 60 * 60;
+
+    client.mint(&user, &(current_time + one_year));
+    client.mint(&other, &(current_time + one_year * 5));
+    client.mint(&user, &(current_time + one_year * 2));
+    client.mint(&user, &0);
+
+    assert_eq!(client.expiration_of_owner_by_index(&user, &0), current_time + one_year);
+    assert_eq!(client.expiration_of_owner_by_index(&user, &1), current_time + one_year * 2);
+    assert_eq!(client.expiration_of_owner_by_index(&user, &2), 0);
+    assert_eq!(client.expiration_of_owner_by_index(&other, &0), current_time + one_year * 5);
+
+    // Index past the owner's balance
+    assert_eq!(client.try_expiration_of_owner_by_index(&user, &3), Err(Ok(Error::IndexOutOfBounds)));
+}
+
+#[test]
+fn test_valid_from_and_is_active() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.initialize(&owner);
+
+    env.ledger().set_timestamp(10_000);
+
+    let active_token = client.mint(&user, &50_000);
+    let scheduled_token = client.mint_with_valid_from(&user, &20_000, &50_000);
+
+    // Both record the issuance time; only the scheduled one is deferred
+    assert_eq!(client.get_issued_at(&active_token), 10_000);
+    assert_eq!(client.get_issued_at(&scheduled_token), 10_000);
+    assert_eq!(client.get_valid_from(&active_token), 10_000);
+    assert_eq!(client.get_valid_from(&scheduled_token), 20_000);
+
+    // Not yet active
+    assert!(client.is_active(&active_token));
+    assert!(!client.is_active(&scheduled_token));
+    assert!(!client.is_expired(&scheduled_token));
+
+    // Active once valid_from is reached
+    env.ledger().set_timestamp(20_000);
+    assert!(client.is_active(&scheduled_token));
+
+    // Expired tokens are no longer active
+    env.ledger().set_timestamp(60_000);
+    assert!(!client.is_active(&active_token));
+    assert!(!client.is_active(&scheduled_token));
+    assert!(client.is_expired(&scheduled_token));
+}
+
+#[test]
+fn test_valid_from_must_precede_expiration() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.initialize(&owner);
+
+    env.ledger().set_timestamp(10_000);
+
+    // A token that would never become active is rejected
+    let result = client.try_mint_with_valid_from(&user, &50_000, &50_000);
+    assert_eq!(result, Err(Ok(Error::InvalidValidFrom)));
+    let result = client.try_mint_with_valid_from(&user, &60_000, &50_000);
+    assert_eq!(result, Err(Ok(Error::InvalidValidFrom)));
+    assert_eq!(client.balance(&user), 0);
+
+    // Tokens without an expiration can start at any time
+    let token_id = client.mint_with_valid_from(&user, &60_000, &0);
+    assert_eq!(client.get_valid_from(&token_id), 60_000);
+    client.mint_with_valid_from(&user, &49_999, &50_000);
+    assert_eq!(client.balance(&user), 2);
+}
+
+#[test]
+fn test_expiry_granularity() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.initialize(&owner);
+
+    let one_day: u64 = 24 * 60 * 60;
+
+    // Rounding is disabled by default
+    assert_eq!(client.get_expiry_granularity(), 0);
+    let unrounded = client.mint(&user, &100_000);
+    assert_eq!(client.get_expiration(&unrounded), 100_000);
+
+    client.set_expiry_granularity(&one_day);
+    assert_eq!(client.get_expiry_granularity(), one_day);
+
+    // Rounded up to the next day boundary
+    let rounded = client.mint(&user, &100_000);
+    assert_eq!(client.get_expiration(&rounded), 2 * one_day);
+
+    // Exact multiples are unchanged
+    let aligned = client.mint(&user, &(3 * one_day));
+    assert_eq!(client.get_expiration(&aligned), 3 * one_day);
+
+    // No-expiration and overflowing values are left alone
+    let no_expiry = client.mint(&user, &0);
+    assert_eq!(client.get_expiration(&no_expiry), 0);
+    let far_future = client.mint(&user, &u64::MAX);
+    assert_eq!(client.get_expiration(&far_future), u64::MAX);
+}
+
+#[test]
+fn test_bulk_renew() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    client.initialize(&owner);
+
+    let one_year: u64 = 365 * 24 * 60 * 60;
+
+    let token_1 = client.mint(&user1, &one_year);
+    let token_2 = client.mint(&user2, &(2 * one_year));
+    let no_expiry = client.mint(&user2, &0);
+    let near_max = client.mint(&user1, &(u64::MAX - 10));
+    let untouched = client.mint(&user1, &one_year);
+
+    let updated = client.bulk_renew(&vec![&env, token_1, token_2, no_expiry, near_max], &one_year);
+    assert_eq!(updated, 3);
+
+    assert_eq!(client.get_expiration(&token_1), 2 * one_year);
+    assert_eq!(client.get_expiration(&token_2), 3 * one_year);
+
+    // No-expiration tokens stay that way, and renewals saturate
+    assert_eq!(client.get_expiration(&no_expiry), 0);
+    assert_eq!(client.get_expiration(&near_max), u64::MAX);
+
+    // Tokens not listed are unchanged
+    assert_eq!(client.get_expiration(&untouched), one_year);
+
+    // Renewed expirations are rounded up to the granularity
+    client.set_expiry_granularity(&1_000);
+    client.bulk_renew(&vec![&env, untouched], &1_500);
+    assert_eq!(client.get_expiration(&untouched), (one_year + 1_500).div_ceil(1_000) * 1_000);
+}
+
+#[test]
+fn test_max_expiry_horizon() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.initialize(&owner);
+
+    env.ledger().set_timestamp(1_000_000);
+    let one_year: u64 = 365 * 24 * 60 * 60;
+    let now = env.ledger().timestamp();
+
+    // No cap by default
+    assert_eq!(client.get_max_expiry_horizon(), 0);
+    client.mint(&user, &u64::MAX);
+    client.mint(&user, &0);
+
+    client.set_max_expiry_horizon(&one_year);
+    assert_eq!(client.get_max_expiry_horizon(), one_year);
+
+    // Within the horizon, up to and including its edge
+    let token_id = client.mint(&user, &(now + 30 * 24 * 60 * 60));
+    assert_eq!(client.get_expiration(&token_id), now + 30 * 24 * 60 * 60);
+    client.mint(&user, &(now + one_year));
+
+    // Beyond the horizon, or never expiring
+    assert_eq!(client.try_mint(&user, &(now + one_year + 1)), Err(Ok(Error::ExpiryTooFar)));
+    assert_eq!(client.try_mint(&user, &u64::MAX), Err(Ok(Error::ExpiryTooFar)));
+    assert_eq!(client.try_mint(&user, &0), Err(Ok(Error::ExpiryTooFar)));
+    assert_eq!(
+        client.try_mint_with_valid_from(&user, &now, &(now + 2 * one_year)),
+        Err(Ok(Error::ExpiryTooFar))
+    );
+    assert_eq!(client.balance(&user), 4);
+
+    // Renewals are capped by the horizon too
+    client.bulk_renew(&vec![&env, token_id], &(30 * 24 * 60 * 60));
+    assert_eq!(client.get_expiration(&token_id), now + 60 * 24 * 60 * 60);
+    assert_eq!(
+        client.try_bulk_renew(&vec![&env, token_id], &one_year),
+        Err(Ok(Error::ExpiryTooFar))
+    );
+    assert_eq!(client.get_expiration(&token_id), now + 60 * 24 * 60 * 60);
+
+    // Removing the cap allows them again
+    client.set_max_expiry_horizon(&0);
+    client.mint(&user, &0);
+}
+
+#[test]
+fn test_max_expiry_horizon_with_granularity() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let user = Address::generate(&env);
+    let recovery_account = Address::generate(&env);
+
+    client.initialize(&owner);
+
+    env.ledger().set_timestamp(1_000_000);
+    let one_day: u64 = 24 * 60 * 60;
+    let one_year: u64 = 365 * one_day;
+    let cap = 1_000_000 + one_year;
+
+    client.set_max_expiry_horizon(&one_year);
+    client.set_expiry_granularity(&one_day);
+
+    // The cap is not a day boundary, so rounding it up would overshoot
+    assert_eq!(client.try_mint(&user, &cap), Err(Ok(Error::ExpiryTooFar)));
+
+    // The last day boundary within the cap is accepted
+    let last_day = cap / one_day * one_day;
+    let token_id = client.mint(&user, &(last_day - 1));
+    assert_eq!(client.get_expiration(&token_id), last_day);
+
+    // Renewals and recovery transfers check the rounded value too
+    let short_lived = client.mint(&user, &(last_day - one_day));
+    assert_eq!(
+        client.try_bulk_renew(&vec![&env, short_lived], &(one_day + 1)),
+        Err(Ok(Error::ExpiryTooFar))
+    );
+    assert_eq!(
+        client.try_transfer_and_renew(&short_lived, &recovery_account, &cap),
+        Err(Ok(Error::ExpiryTooFar))
+    );
+    assert_eq!(client.get_expiration(&short_lived), last_day - one_day);
+    assert_eq!(client.owner_of(&short_lived), user);
+}
+
+#[test]
+fn test_transfer_allowlist() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let escrow = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    client.initialize(&owner);
+
+    let token_id = client.mint(&holder, &0);
+
+    // Transfers to a random address are rejected
+    assert_eq!(client.try_transfer(&holder, &stranger, &token_id), Err(Ok(Error::TransferNotAllowed.into())));
+    assert_eq!(
+        client.try_transfer_from(&stranger, &holder, &stranger, &token_id),
+        Err(Ok(Error::TransferNotAllowed.into()))
+    );
+    assert_eq!(client.owner_of(&token_id), holder);
+
+    client.set_transfer_allowed(&escrow, &true);
+    assert!(client.is_transfer_allowed(&escrow));
+
+    // To and from an allowlisted address succeed
+    client.transfer(&holder, &escrow, &token_id);
+    assert_eq!(client.owner_of(&token_id), escrow);
+    client.transfer(&escrow, &stranger, &token_id);
+    assert_eq!(client.owner_of(&token_id), stranger);
+    assert_eq!(client.get_owner_token_id(&stranger, &0), token_id);
+
+    // Removing it from the allowlist restores the restriction
+    client.set_transfer_allowed(&escrow, &false);
+    assert!(!client.is_transfer_allowed(&escrow));
+    assert_eq!(client.try_transfer(&stranger, &escrow, &token_id), Err(Ok(Error::TransferNotAllowed.into())));
+}
+
+#[test]
+fn test_next_token_id() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.initialize(&owner);
+    assert_eq!(client.next_token_id(), 0);
+
+    let token_id = client.mint(&user, &0);
+    client.mint(&user, &0);
+    assert_eq!(client.next_token_id(), 2);
+
+    // Burning lowers total_supply but not the next id
+    client.surrender(&token_id);
+    assert_eq!(client.total_supply(), 1);
+    assert_eq!(client.next_token_id(), 2);
+    assert_eq!(client.mint(&user, &0), 2);
+}
+
+#[test]
+fn test_active_supply() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.initialize(&owner);
+
+    env.ledger().set_timestamp(1_000);
+
+    client.mint(&user, &2_000);
+    client.mint(&user, &5_000);
+    client.mint(&user, &0);
+    client.mint(&user, &3_000);
+    assert_eq!(client.active_supply(), 4);
+
+    // Two tokens expire
+    env.ledger().set_timestamp(3_500);
+    assert_eq!(client.total_supply(), 4);
+    assert_eq!(client.active_supply(), 2);
+
+    // Pages add up to the full count
+    assert_eq!(client.active_supply_paginated(&0, &2), 1);
+    assert_eq!(client.active_supply_paginated(&2, &2), 1);
+    assert_eq!(client.active_supply_paginated(&2, &100), 1);
+    assert_eq!(client.active_supply_paginated(&10, &5), 0);
+}
+
+#[test]
+fn test_supply_breakdown() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.initialize(&owner);
+
+    env.ledger().set_timestamp(1_000);
+
+    client.mint(&user, &2_000); // expires before the check
+    client.mint(&user, &0); // never expires
+    client.mint(&user, &5_000);
+    client.mint_with_valid_from(&user, &4_000, &9_000); // not yet valid
+    client.mint(&user, &2_500); // expires before the check
+
+    env.ledger().set_timestamp(3_000);
+    assert_eq!(client.supply_breakdown(), (2, 2, 1));
+
+    // The active count agrees with active_supply and is_active
+    assert_eq!(client.active_supply(), 2);
+    assert!(!client.is_active(&3));
+
+    // Pages sum to the full breakdown
+    assert_eq!(client.supply_breakdown_paginated(&0, &3), (2, 1, 0));
+    assert_eq!(client.supply_breakdown_paginated(&3, &3), (0, 1, 1));
+
+    // The pre-issued token activates, then everything but the eternal one expires
+    env.ledger().set_timestamp(4_000);
+    assert_eq!(client.supply_breakdown(), (3, 2, 0));
+    env.ledger().set_timestamp(10_000);
+    assert_eq!(client.supply_breakdown(), (1, 4, 0));
+}
+
+#[test]
+fn test_mint_replacement() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.initialize(&owner);
+
+    let one_year: u64 = 365 * 24 * 60 * 60;
+    let original = client.mint(&user, &one_year);
+    assert_eq!(client.get_replaces(&original), None);
+
+    // Replacement that keeps the old token
+    let renewal = client.mint_replacement(&user, &(2 * one_year), &original, &false);
+    assert_eq!(client.get_replaces(&renewal), Some(original));
+    assert_eq!(client.get_replaced_by(&original), Some(renewal));
+    assert_eq!(client.owner_of(&original), user);
+    assert_eq!(client.get_expiration(&renewal), 2 * one_year);
+    assert_eq!(client.balance(&user), 2);
+
+    // Replacement that burns the old token; the links outlive it
+    let second_renewal = client.mint_replacement(&user, &(3 * one_year), &renewal, &true);
+    assert_eq!(client.get_replaces(&second_renewal), Some(renewal));
+    assert_eq!(client.get_replaced_by(&renewal), Some(second_renewal));
+    assert!(client.try_owner_of(&renewal).is_err());
+    assert_eq!(client.get_expiration(&renewal), 0);
+    assert_eq!(client.balance(&user), 2);
+}
+
+#[test]
+fn test_expiry_set_events() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.initialize(&owner);
+
+    let one_year: u64 = 365 * 24 * 60 * 60;
+
+    let token_id = client.mint(&user, &one_year);
+    assert_emitted(&env, &contract_addr, ExpirySet { token_id, expires_at: one_year });
+
+    let eternal = client.mint(&user, &0);
+    assert_emitted(&env, &contract_addr, ExpirySet { token_id: eternal, expires_at: 0 });
+
+    // Renewals emit the new expiration
+    client.bulk_renew(&vec![&env, token_id], &one_year);
+    assert_emitted(&env, &contract_addr, ExpirySet { token_id, expires_at: 2 * one_year });
+
+    // Rounded expirations are reported as stored
+    client.set_expiry_granularity(&1_000);
+    let rounded = client.mint(&user, &1_500);
+    assert_emitted(&env, &contract_addr, ExpirySet { token_id: rounded, expires_at: 2_000 });
+}
+
+#[test]
+fn test_burn_disabled() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.initialize(&owner);
+    assert!(client.is_burnable());
+
+    let token_id = client.mint(&user, &0);
+
+    client.disable_burning();
+    assert!(!client.is_burnable());
+
+    assert_eq!(client.try_surrender(&token_id), Err(Ok(Error::BurnDisabled)));
+    assert_eq!(client.try_burn_from(&owner, &token_id), Err(Ok(Error::BurnDisabled)));
+    assert_eq!(client.try_mint_replacement(&user, &0, &token_id, &true), Err(Ok(Error::BurnDisabled)));
+    assert_eq!(client.owner_of(&token_id), user);
+
+    // Replacements that keep the old token still work
+    let renewal = client.mint_replacement(&user, &0, &token_id, &false);
+    assert_eq!(client.get_replaces(&renewal), Some(token_id));
+
+    // Disabling again is a no-op; burning stays off
+    client.disable_burning();
+    assert!(!client.is_burnable());
+    assert_eq!(client.try_surrender(&renewal), Err(Ok(Error::BurnDisabled)));
+    assert_eq!(client.balance(&user), 2);
+}
+
+#[test]
+fn test_holder_count() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let escrow = Address::generate(&env);
+
+    client.initialize(&owner);
+    assert_eq!(client.get_holder_count(), 0);
+
+    // Several tokens to the same user count once
+    let token_1 = client.mint(&user1, &0);
+    let token_2 = client.mint(&user1, &0);
+    assert_eq!(client.get_holder_count(), 1);
+
+    let token_3 = client.mint(&user2, &0);
+    assert_eq!(client.get_holder_count(), 2);
+
+    // Burning one of two tokens keeps the holder
+    client.surrender(&token_1);
+    assert_eq!(client.get_holder_count(), 2);
+
+    // Transfers move holders around
+    client.set_transfer_allowed(&escrow, &true);
+    client.transfer(&user2, &escrow, &token_3);
+    assert_eq!(client.get_holder_count(), 2);
+    client.force_transfer(&token_3, &user1);
+    assert_eq!(client.get_holder_count(), 1);
+    client.force_transfer(&token_3, &user1);
+    assert_eq!(client.get_holder_count(), 1);
+
+    // Burning the last tokens empties the tally
+    client.burn_from(&owner, &token_2);
+    assert_eq!(client.get_holder_count(), 1);
+    client.surrender(&token_3);
+    assert_eq!(client.get_holder_count(), 0);
+}
+
+#[test]
+fn test_expiry_histogram() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.initialize(&owner);
+
+    let day: u64 = 24 * 60 * 60;
+    let now: u64 = 100 * day;
+    env.ledger().set_timestamp(now - 10 * day);
+
+    client.mint(&user, &(now - day)); // expired by the query
+    client.mint(&user, &(now + day / 2));
+    client.mint(&user, &(now + day));
+    client.mint(&user, &(now + 3 * day));
+    client.mint(&user, &(now + 20 * day));
+    client.mint(&user, &(now + 90 * day)); // beyond the last edge
+    client.mint(&user, &0); // never expires
+
+    env.ledger().set_timestamp(now);
+
+    let edges = vec![&env, day, 7 * day, 30 * day];
+    assert_eq!(client.expiry_histogram(&edges), vec![&env, 2, 1, 1]);
+
+    // Pages add up to the full histogram
+    assert_eq!(client.expiry_histogram_paginated(&edges, &0, &3), vec![&env, 2, 0, 0]);
+    assert_eq!(client.expiry_histogram_paginated(&edges, &3, &10), vec![&env, 0, 1, 1]);
+
+    // Edges must be strictly increasing, and scans are capped
+    let unsorted = vec![&env, 7 * day, day];
+    assert_eq!(client.try_expiry_histogram(&unsorted), Err(Ok(Error::InvalidBuckets)));
+    assert_eq!(client.try_expiry_histogram_paginated(&edges, &0, &1_000), Err(Ok(Error::ScanTooLarge)));
+}
+
+#[test]
+fn test_mint_for_duration() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.initialize(&owner);
+
+    env.ledger().set_timestamp(1_000_000);
+    let one_year: u64 = 365 * 24 * 60 * 60;
+
+    let token_id = client.mint_for_duration(&user, &one_year);
+    assert_eq!(client.get_expiration(&token_id), 1_000_000 + one_year);
+    assert_eq!(client.owner_of(&token_id), user);
+
+    // 0 means no expiration, as with `mint`
+    let eternal = client.mint_for_duration(&user, &0);
+    assert_eq!(client.get_expiration(&eternal), 0);
+
+    // Very long durations saturate instead of overflowing
+    let capped = client.mint_for_duration(&user, &u64::MAX);
+    assert_eq!(client.get_expiration(&capped), u64::MAX);
+}
+
+#[test]
+fn test_transfer_and_renew() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let lost_account = Address::generate(&env);
+    let recovery_account = Address::generate(&env);
+
+    client.initialize(&owner);
+
+    let one_year: u64 = 365 * 24 * 60 * 60;
+    let token_id = client.mint(&lost_account, &one_year);
+
+    client.transfer_and_renew(&token_id, &recovery_account, &(3 * one_year));
+    assert_emitted(&env, &contract_addr, Recovery { token_id, from: lost_account.clone(), to: recovery_account.clone() });
+    assert_emitted(&env, &contract_addr, ExpirySet { token_id, expires_at: 3 * one_year });
+
+    assert_eq!(client.owner_of(&token_id), recovery_account);
+    assert_eq!(client.get_expiration(&token_id), 3 * one_year);
+    assert_eq!(client.balance(&lost_account), 0);
+    assert_eq!(client.get_owner_token_id(&recovery_account, &0), token_id);
+}
+
+#[test]
+fn test_issuer_per_minter() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let minter1 = Address::generate(&env);
+    let minter2 = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.initialize(&owner);
+
+    // Unapproved minters are rejected
+    assert_eq!(client.try_mint_by(&minter1, &user, &0), Err(Ok(Error::NotMinter)));
+
+    client.set_minter(&minter1, &true);
+    client.set_minter(&minter2, &true);
+    assert!(client.is_minter(&minter1));
+
+    let token_1 = client.mint_by(&minter1, &user, &0);
+    let token_2 = client.mint_by(&minter2, &user, &0);
+    let token_3 = client.mint(&user, &0);
+
+    assert_eq!(client.get_issuer(&token_1), Some(minter1.clone()));
+    assert_eq!(client.get_issuer(&token_2), Some(minter2));
+    assert_eq!(client.get_issuer(&token_3), Some(owner));
+    assert_eq!(client.balance(&user), 3);
+
+    client.set_minter(&minter1, &false);
+    assert_eq!(client.try_mint_by(&minter1, &user, &0), Err(Ok(Error::NotMinter)));
+
+    // Burning clears the issuer with the rest of the token's data
+    client.surrender(&token_1);
+    assert_eq!(client.get_issuer(&token_1), None);
+}
+
+#[test]
+fn test_token_data_in_persistent_storage() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_addr = env.register(INZPEKTORID, ());
+    let client = INZPEKTORIDClient::new(&env, &contract_addr);
+
+    let owner = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.initialize(&owner);
+
+    let original = client.mint(&user, &1_000);
+    let token_id = client.mint_replacement(&user, &2_000, &original, &false);
+
+    // Per-token data stays out of the instance entry
+    env.as_contract(&contract_addr, || {
+        let keys = [
+            DataKey::Expiration(token_id),
+            DataKey::IssuedAt(token_id),
+            DataKey::Issuer(token_id),
+            DataKey::Replaces(token_id),
+            DataKey::ReplacedBy(original),
+        ];
+        for key in keys.iter() {
+            assert!(!env.storage().instance().has(key));
+            assert!(env.storage().persistent().has(key));
+            assert!(env.storage().persistent().get_ttl(key) > 0);
+        }
+    });
+}